@@ -0,0 +1,53 @@
+CREATE TABLE accounts (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'exchange', 'wallet', 'lightning'
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Events can optionally be attributed to an account (exchange, cold wallet, node...)
+ALTER TABLE exchange_transactions ADD COLUMN account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL;
+ALTER TABLE onchain_fees ADD COLUMN account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL;
+
+-- Watch-only wallets. Only public key material is stored, addresses are derived locally.
+CREATE TABLE wallets (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    label TEXT NOT NULL,
+    descriptor TEXT NOT NULL, -- normalized output descriptor (ypub/zpub are converted to xpub form)
+    script_type TEXT NOT NULL, -- 'p2pkh', 'p2sh-p2wpkh', 'p2wpkh', 'p2tr'
+    network TEXT NOT NULL, -- 'bitcoin', 'testnet'
+    receive_gap_limit INTEGER NOT NULL DEFAULT 20,
+    change_gap_limit INTEGER NOT NULL DEFAULT 20,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(descriptor)
+);
+
+CREATE TABLE wallet_addresses (
+    wallet_id TEXT NOT NULL REFERENCES wallets(id) ON DELETE CASCADE,
+    chain INTEGER NOT NULL, -- 0 = receive, 1 = change
+    derivation_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    used BOOLEAN NOT NULL DEFAULT 0,
+    PRIMARY KEY (wallet_id, chain, derivation_index),
+    UNIQUE(address)
+);
+
+-- On-chain activity imported from wallet exports (Sparrow, Electrum, ...), matched to a wallet
+CREATE TABLE wallet_activity (
+    id TEXT PRIMARY KEY,
+    wallet_id TEXT NOT NULL REFERENCES wallets(id) ON DELETE CASCADE,
+    txid TEXT NOT NULL,
+    vout INTEGER NOT NULL DEFAULT -1, -- -1 when the export is per transaction rather than per output
+    address TEXT,
+    amount_sats INTEGER NOT NULL, -- signed: positive = received, negative = sent (including fee)
+    fee_sats INTEGER,
+    memo TEXT,
+    timestamp DATETIME, -- NULL while the transaction is unconfirmed
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(wallet_id, txid, vout)
+);
+
+CREATE INDEX idx_wallet_activity_wallet_timestamp ON wallet_activity(wallet_id, timestamp);
+CREATE INDEX idx_exchange_transactions_account ON exchange_transactions(account_id);
+CREATE INDEX idx_onchain_fees_account ON onchain_fees(account_id);
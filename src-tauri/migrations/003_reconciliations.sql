@@ -0,0 +1,23 @@
+-- Manual corrections that explain the difference between tracked and observed balances
+CREATE TABLE balance_adjustments (
+    id TEXT PRIMARY KEY,
+    account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL,
+    amount_sats INTEGER NOT NULL, -- signed: positive adds to the stack, negative removes from it
+    memo TEXT,
+    timestamp DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Balances observed outside the app (hardware wallet screen, exchange balance page...)
+CREATE TABLE reconciliations (
+    id TEXT PRIMARY KEY,
+    account_id TEXT REFERENCES accounts(id) ON DELETE CASCADE, -- NULL = the whole stack
+    observed_sats INTEGER NOT NULL,
+    observed_at DATETIME NOT NULL,
+    memo TEXT,
+    adjustment_id TEXT REFERENCES balance_adjustments(id) ON DELETE SET NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_balance_adjustments_timestamp ON balance_adjustments(timestamp DESC);
+CREATE INDEX idx_reconciliations_observed_at ON reconciliations(observed_at DESC);
@@ -0,0 +1,17 @@
+-- Lightning amounts are stored in millisatoshis so sub-sat routing fees are not lost
+CREATE TABLE lightning_payments (
+    id TEXT PRIMARY KEY,
+    direction TEXT NOT NULL, -- 'outgoing', 'incoming'
+    amount_msats INTEGER NOT NULL, -- excluding routing fee
+    routing_fee_msats INTEGER NOT NULL DEFAULT 0,
+    payment_hash TEXT,
+    memo TEXT,
+    timestamp DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    provider_id TEXT, -- import identifier, NULL for manual payments
+    account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL, -- node or wallet the payment was made from
+    UNIQUE(provider_id)
+);
+
+CREATE INDEX idx_lightning_payments_timestamp ON lightning_payments(timestamp DESC);
+CREATE INDEX idx_lightning_payments_account ON lightning_payments(account_id);
@@ -0,0 +1,15 @@
+-- Sats spent on goods and services, as opposed to sold for fiat
+CREATE TABLE spending_events (
+    id TEXT PRIMARY KEY,
+    amount_sats INTEGER NOT NULL,
+    fiat_value_cents INTEGER, -- market value of the spent sats at spend time
+    merchant TEXT,
+    category TEXT,
+    memo TEXT,
+    timestamp DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_spending_events_timestamp ON spending_events(timestamp DESC);
+CREATE INDEX idx_spending_events_category ON spending_events(category);
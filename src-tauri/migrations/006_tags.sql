@@ -0,0 +1,44 @@
+CREATE TABLE tags (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    color TEXT, -- optional hex color for display
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Events live in separate tables, so the join is keyed by (event_type, event_id)
+-- where event_type matches the unified events record_type
+CREATE TABLE event_tags (
+    tag_id TEXT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL, -- 'exchange_transaction', 'onchain_fee', 'balance_adjustment', 'lightning_payment', 'spending'
+    event_id TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tag_id, event_type, event_id)
+);
+
+CREATE INDEX idx_event_tags_event ON event_tags(event_type, event_id);
+
+-- No foreign key can point at several tables, so clean up assignments when an event goes away
+CREATE TRIGGER exchange_transactions_delete_tags AFTER DELETE ON exchange_transactions
+BEGIN
+    DELETE FROM event_tags WHERE event_type = 'exchange_transaction' AND event_id = OLD.id;
+END;
+
+CREATE TRIGGER onchain_fees_delete_tags AFTER DELETE ON onchain_fees
+BEGIN
+    DELETE FROM event_tags WHERE event_type = 'onchain_fee' AND event_id = OLD.id;
+END;
+
+CREATE TRIGGER balance_adjustments_delete_tags AFTER DELETE ON balance_adjustments
+BEGIN
+    DELETE FROM event_tags WHERE event_type = 'balance_adjustment' AND event_id = OLD.id;
+END;
+
+CREATE TRIGGER lightning_payments_delete_tags AFTER DELETE ON lightning_payments
+BEGIN
+    DELETE FROM event_tags WHERE event_type = 'lightning_payment' AND event_id = OLD.id;
+END;
+
+CREATE TRIGGER spending_events_delete_tags AFTER DELETE ON spending_events
+BEGIN
+    DELETE FROM event_tags WHERE event_type = 'spending' AND event_id = OLD.id;
+END;
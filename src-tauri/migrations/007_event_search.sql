@@ -0,0 +1,116 @@
+-- Full-text index over event memos and identifiers. Lightning payment hashes go in tx_hash,
+-- spending merchant and category in labels.
+CREATE VIRTUAL TABLE event_search USING fts5(
+    record_type UNINDEXED,
+    event_id UNINDEXED,
+    memo,
+    provider_id,
+    tx_hash,
+    labels,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+SELECT 'exchange_transaction', id, memo, provider_id, NULL, NULL FROM exchange_transactions;
+
+CREATE TRIGGER exchange_transactions_search_insert AFTER INSERT ON exchange_transactions
+BEGIN
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('exchange_transaction', NEW.id, NEW.memo, NEW.provider_id, NULL, NULL);
+END;
+
+CREATE TRIGGER exchange_transactions_search_update AFTER UPDATE ON exchange_transactions
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'exchange_transaction' AND event_id = OLD.id;
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('exchange_transaction', NEW.id, NEW.memo, NEW.provider_id, NULL, NULL);
+END;
+
+CREATE TRIGGER exchange_transactions_search_delete AFTER DELETE ON exchange_transactions
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'exchange_transaction' AND event_id = OLD.id;
+END;
+
+INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+SELECT 'onchain_fee', id, memo, NULL, tx_hash, NULL FROM onchain_fees;
+
+CREATE TRIGGER onchain_fees_search_insert AFTER INSERT ON onchain_fees
+BEGIN
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('onchain_fee', NEW.id, NEW.memo, NULL, NEW.tx_hash, NULL);
+END;
+
+CREATE TRIGGER onchain_fees_search_update AFTER UPDATE ON onchain_fees
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'onchain_fee' AND event_id = OLD.id;
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('onchain_fee', NEW.id, NEW.memo, NULL, NEW.tx_hash, NULL);
+END;
+
+CREATE TRIGGER onchain_fees_search_delete AFTER DELETE ON onchain_fees
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'onchain_fee' AND event_id = OLD.id;
+END;
+
+INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+SELECT 'balance_adjustment', id, memo, NULL, NULL, NULL FROM balance_adjustments;
+
+CREATE TRIGGER balance_adjustments_search_insert AFTER INSERT ON balance_adjustments
+BEGIN
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('balance_adjustment', NEW.id, NEW.memo, NULL, NULL, NULL);
+END;
+
+CREATE TRIGGER balance_adjustments_search_update AFTER UPDATE ON balance_adjustments
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'balance_adjustment' AND event_id = OLD.id;
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('balance_adjustment', NEW.id, NEW.memo, NULL, NULL, NULL);
+END;
+
+CREATE TRIGGER balance_adjustments_search_delete AFTER DELETE ON balance_adjustments
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'balance_adjustment' AND event_id = OLD.id;
+END;
+
+INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+SELECT 'lightning_payment', id, memo, provider_id, payment_hash, NULL FROM lightning_payments;
+
+CREATE TRIGGER lightning_payments_search_insert AFTER INSERT ON lightning_payments
+BEGIN
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('lightning_payment', NEW.id, NEW.memo, NEW.provider_id, NEW.payment_hash, NULL);
+END;
+
+CREATE TRIGGER lightning_payments_search_update AFTER UPDATE ON lightning_payments
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'lightning_payment' AND event_id = OLD.id;
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('lightning_payment', NEW.id, NEW.memo, NEW.provider_id, NEW.payment_hash, NULL);
+END;
+
+CREATE TRIGGER lightning_payments_search_delete AFTER DELETE ON lightning_payments
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'lightning_payment' AND event_id = OLD.id;
+END;
+
+INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+SELECT 'spending', id, memo, NULL, NULL, TRIM(COALESCE(merchant, '') || ' ' || COALESCE(category, '')) FROM spending_events;
+
+CREATE TRIGGER spending_events_search_insert AFTER INSERT ON spending_events
+BEGIN
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('spending', NEW.id, NEW.memo, NULL, NULL, TRIM(COALESCE(NEW.merchant, '') || ' ' || COALESCE(NEW.category, '')));
+END;
+
+CREATE TRIGGER spending_events_search_update AFTER UPDATE ON spending_events
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'spending' AND event_id = OLD.id;
+    INSERT INTO event_search (record_type, event_id, memo, provider_id, tx_hash, labels)
+    VALUES ('spending', NEW.id, NEW.memo, NULL, NULL, TRIM(COALESCE(NEW.merchant, '') || ' ' || COALESCE(NEW.category, '')));
+END;
+
+CREATE TRIGGER spending_events_search_delete AFTER DELETE ON spending_events
+BEGIN
+    DELETE FROM event_search WHERE record_type = 'spending' AND event_id = OLD.id;
+END;
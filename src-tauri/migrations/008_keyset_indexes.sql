@@ -0,0 +1,6 @@
+-- (timestamp, id) indexes back the keyset pagination cursors
+CREATE INDEX idx_exchange_transactions_timestamp_id ON exchange_transactions(timestamp DESC, id DESC);
+CREATE INDEX idx_onchain_fees_timestamp_id ON onchain_fees(timestamp DESC, id DESC);
+CREATE INDEX idx_balance_adjustments_timestamp_id ON balance_adjustments(timestamp DESC, id DESC);
+CREATE INDEX idx_lightning_payments_timestamp_id ON lightning_payments(timestamp DESC, id DESC);
+CREATE INDEX idx_spending_events_timestamp_id ON spending_events(timestamp DESC, id DESC);
@@ -0,0 +1,10 @@
+-- Daily BTC/USD market prices, used to value events at the time they happened
+CREATE TABLE price_history (
+    date TEXT PRIMARY KEY, -- UTC calendar day, 'YYYY-MM-DD'
+    open_cents INTEGER,
+    high_cents INTEGER,
+    low_cents INTEGER,
+    close_cents INTEGER NOT NULL,
+    source TEXT, -- where the price came from, e.g. 'csv' or a price provider name
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Stacking targets, e.g. "1 BTC by 2030" or "21M sats this year"
+CREATE TABLE goals (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    target_sats INTEGER NOT NULL CHECK (target_sats > 0),
+    start_date DATETIME, -- count sats stacked since this date, NULL to count total holdings
+    target_date DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
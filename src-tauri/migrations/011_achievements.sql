@@ -0,0 +1,6 @@
+-- Achievements earned so far, keyed by the achievement definitions in the app
+CREATE TABLE achievements (
+    key TEXT PRIMARY KEY,
+    reached_at DATETIME NOT NULL, -- when replaying the event history first satisfied it
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,16 @@
+-- Recurring buy commitments, e.g. "$50 every Monday on River"
+CREATE TABLE dca_plans (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL CHECK (amount_cents > 0),
+    frequency TEXT NOT NULL CHECK (frequency IN ('daily', 'weekly', 'biweekly', 'monthly')),
+    day_of_week INTEGER CHECK (day_of_week BETWEEN 0 AND 6), -- 0 = Monday, weekly plans only
+    day_of_month INTEGER CHECK (day_of_month BETWEEN 1 AND 31), -- monthly plans, clamped to short months
+    provider TEXT, -- unified events provider the buys must come from, NULL for any
+    account_id TEXT REFERENCES accounts(id) ON DELETE SET NULL,
+    timezone TEXT, -- IANA name the schedule is kept in, NULL for UTC
+    grace_days INTEGER NOT NULL DEFAULT 2, -- how late a buy may be and still count
+    start_date DATETIME NOT NULL,
+    end_date DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
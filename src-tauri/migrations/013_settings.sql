@@ -0,0 +1,6 @@
+-- User preferences, one JSON encoded value per key. Missing keys fall back to built-in defaults.
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
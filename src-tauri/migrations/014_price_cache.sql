@@ -0,0 +1,8 @@
+-- Last good price quote per currency, shown as a stale price after restarts or while offline
+CREATE TABLE price_cache (
+    currency TEXT PRIMARY KEY,
+    price REAL NOT NULL,
+    percent_change_24hr REAL,
+    source TEXT NOT NULL, -- price provider that returned the quote
+    fetched_at DATETIME NOT NULL
+);
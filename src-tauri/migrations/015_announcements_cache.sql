@@ -0,0 +1,7 @@
+-- Last announcements fetched, served while offline
+CREATE TABLE announcements_cache (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    latest_version TEXT NOT NULL,
+    announcements TEXT NOT NULL, -- JSON array of strings
+    fetched_at DATETIME NOT NULL
+);
@@ -0,0 +1,125 @@
+use crate::models::account::{Account, CreateAccountRequest, UpdateAccountRequest};
+use chrono::Utc;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteExecutor, SqlitePool};
+use tauri::State;
+use uuid::Uuid;
+
+fn account_from_row(row: &SqliteRow) -> Result<Account, String> {
+    Ok(Account {
+        id: row.get("id"),
+        name: row.get("name"),
+        kind: row
+            .get::<String, _>("kind")
+            .parse()
+            .map_err(|e| format!("Invalid account kind: {}", e))?,
+        created_at: row.get("created_at"),
+    })
+}
+
+pub(crate) async fn insert_account<'e>(
+    executor: impl SqliteExecutor<'e>,
+    request: CreateAccountRequest,
+) -> Result<Account, String> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Account name cannot be empty".to_string());
+    }
+
+    let account = Account {
+        id: Uuid::new_v4().to_string(),
+        name,
+        kind: request.kind,
+        created_at: Utc::now(),
+    };
+
+    sqlx::query("INSERT INTO accounts (id, name, kind, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&account.id)
+        .bind(&account.name)
+        .bind(account.kind.to_string())
+        .bind(account.created_at)
+        .execute(executor)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(account)
+}
+
+#[tauri::command]
+pub async fn create_account(
+    pool: State<'_, SqlitePool>,
+    request: CreateAccountRequest,
+) -> Result<Account, String> {
+    let account = insert_account(pool.inner(), request).await?;
+
+    println!("Created account: {:?}", account);
+    Ok(account)
+}
+
+#[tauri::command]
+pub async fn get_accounts(pool: State<'_, SqlitePool>) -> Result<Vec<Account>, String> {
+    let rows = sqlx::query("SELECT id, name, kind, created_at FROM accounts ORDER BY name ASC")
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let accounts = rows
+        .iter()
+        .map(account_from_row)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    println!("Retrieved {} accounts", accounts.len());
+    Ok(accounts)
+}
+
+#[tauri::command]
+pub async fn update_account(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    request: UpdateAccountRequest,
+) -> Result<Account, String> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Account name cannot be empty".to_string());
+    }
+
+    let result = sqlx::query("UPDATE accounts SET name = ?, kind = ? WHERE id = ?")
+        .bind(&name)
+        .bind(request.kind.to_string())
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Account not found".to_string());
+    }
+
+    let row = sqlx::query("SELECT id, name, kind, created_at FROM accounts WHERE id = ?")
+        .bind(&id)
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let updated_account = account_from_row(&row)?;
+
+    println!("Updated account: {:?}", updated_account);
+    Ok(updated_account)
+}
+
+#[tauri::command]
+pub async fn delete_account(pool: State<'_, SqlitePool>, id: String) -> Result<(), String> {
+    // Events keep existing without an account (ON DELETE SET NULL), watch-only wallets are removed
+    let result = sqlx::query("DELETE FROM accounts WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Account not found".to_string());
+    }
+
+    println!("Deleted account with id: {}", id);
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use crate::commands::activity_tool::{calculate_streaks, period_index};
+use crate::commands::performance::{load_flows, Flow};
+use crate::commands::settings::{load_settings, parse_week_start};
+use crate::commands::time_buckets::parse_timezone;
+use crate::models::achievement::{AchievementsReport, EarnedAchievement, UpcomingAchievement};
+use crate::models::activity_metrics::StreakCadence;
+use chrono::{DateTime, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Holdings thresholds in sats.
+const HOLDINGS_ACHIEVEMENTS: [(i64, &str); 5] = [
+    (100_000, "First 100k sats"),
+    (1_000_000, "First 1M sats"),
+    (10_000_000, "First 10M sats"),
+    (21_000_000, "First 21M sats"),
+    (100_000_000, "First whole coin"),
+];
+
+/// Consecutive weeks with at least one buy, in the timezone and week start from settings.
+const STREAK_ACHIEVEMENTS: [(i64, &str); 5] = [
+    (4, "4 consecutive weeks"),
+    (13, "13 consecutive weeks"),
+    (26, "26 consecutive weeks"),
+    (52, "52 consecutive weeks"),
+    (100, "100 consecutive weeks"),
+];
+
+/// Days holding without a single sell, counted from the first buy or the latest sell.
+const HODL_ACHIEVEMENTS: [(i64, &str); 2] = [
+    (365, "1 year without selling"),
+    (1461, "4 years without selling"),
+];
+
+fn holdings_key(threshold: i64) -> String {
+    format!("holdings_{}", threshold)
+}
+
+fn streak_key(weeks: i64) -> String {
+    format!("streak_{}_weeks", weeks)
+}
+
+fn hodl_key(days: i64) -> String {
+    format!("hodl_{}_days", days)
+}
+
+/// Title and category of a stored achievement key, None for keys no longer defined.
+fn describe(key: &str) -> Option<(&'static str, &'static str)> {
+    HOLDINGS_ACHIEVEMENTS
+        .iter()
+        .find(|(threshold, _)| holdings_key(*threshold) == key)
+        .map(|(_, title)| (*title, "holdings"))
+        .or_else(|| {
+            STREAK_ACHIEVEMENTS
+                .iter()
+                .find(|(weeks, _)| streak_key(*weeks) == key)
+                .map(|(_, title)| (*title, "streak"))
+        })
+        .or_else(|| {
+            HODL_ACHIEVEMENTS
+                .iter()
+                .find(|(days, _)| hodl_key(*days) == key)
+                .map(|(_, title)| (*title, "hodl"))
+        })
+}
+
+/// Reached dates plus the state needed for upcoming achievements: holdings, the active weekly
+/// streak and the start of the current run without sells.
+struct Replay {
+    reached: HashMap<String, DateTime<Utc>>,
+    holdings_sats: i64,
+    active_streak_weeks: i64,
+    hodl_since: Option<DateTime<Utc>>,
+}
+
+/// Records the hodl achievements reached by a run without sells from `since` to `until`.
+fn record_hodl(reached: &mut HashMap<String, DateTime<Utc>>, since: DateTime<Utc>, until: DateTime<Utc>) {
+    for (days, _) in HODL_ACHIEVEMENTS {
+        let reached_at = since + Duration::days(days);
+        if reached_at <= until {
+            reached.entry(hodl_key(days)).or_insert(reached_at);
+        }
+    }
+}
+
+/// Replays all events chronologically, recording when each achievement was first reached.
+/// Weeks are numbered with the same `period_index` the activity streaks use, so both agree.
+fn replay(flows: &[Flow], now: DateTime<Utc>, tz: &Tz, week_start: Weekday) -> Replay {
+    let mut reached: HashMap<String, DateTime<Utc>> = HashMap::new();
+    let mut holdings_msats = 0i64;
+    let mut streak_weeks = 0i64;
+    let mut last_buy_week: Option<i64> = None;
+    let mut buy_dates: Vec<NaiveDate> = Vec::new();
+    let mut hodl_since: Option<DateTime<Utc>> = None;
+
+    for flow in flows {
+        holdings_msats += flow.msats_delta;
+        for (threshold, _) in HOLDINGS_ACHIEVEMENTS {
+            if holdings_msats >= threshold * 1000 {
+                reached.entry(holdings_key(threshold)).or_insert(flow.timestamp);
+            }
+        }
+
+        match flow.kind.as_str() {
+            "buy" => {
+                let date = flow.timestamp.with_timezone(tz).date_naive();
+                let week = period_index(date, StreakCadence::Weekly, week_start, date);
+                buy_dates.push(date);
+                match last_buy_week {
+                    Some(last) if last == week => {}
+                    Some(last) if week - last == 1 => streak_weeks += 1,
+                    _ => streak_weeks = 1,
+                }
+                if last_buy_week != Some(week) {
+                    for (weeks, _) in STREAK_ACHIEVEMENTS {
+                        if streak_weeks >= weeks {
+                            reached.entry(streak_key(weeks)).or_insert(flow.timestamp);
+                        }
+                    }
+                }
+                last_buy_week = Some(week);
+
+                if hodl_since.is_none() {
+                    hodl_since = Some(flow.timestamp);
+                }
+            }
+            "sell" => {
+                if let Some(since) = hodl_since {
+                    record_hodl(&mut reached, since, flow.timestamp);
+                }
+                hodl_since = Some(flow.timestamp);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(since) = hodl_since {
+        record_hodl(&mut reached, since, now);
+    }
+
+    let today = now.with_timezone(tz).date_naive();
+    let (active_streak_weeks, _) =
+        calculate_streaks(&buy_dates, StreakCadence::Weekly, week_start, today);
+
+    Replay {
+        reached,
+        holdings_sats: holdings_msats / 1000,
+        active_streak_weeks: active_streak_weeks as i64,
+        hodl_since,
+    }
+}
+
+/// Lists earned and upcoming achievements.
+///
+/// Achievements are derived by replaying every event in order and persisted with the date they
+/// were reached, so they stay in the history even if later edits would no longer earn them.
+/// A replay that finds an earlier date (for example after importing older history) moves the
+/// stored date back.
+#[tauri::command]
+pub async fn get_achievements(pool: State<'_, SqlitePool>) -> Result<AchievementsReport, String> {
+    let now = Utc::now();
+    let settings = load_settings(pool.inner()).await?;
+    let tz = parse_timezone(settings.timezone.as_deref())?;
+    let week_start = parse_week_start(&settings.week_start)?;
+    let flows = load_flows(pool.inner(), now).await?;
+    let replay = replay(&flows, now, &tz, week_start);
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let stored_rows = sqlx::query("SELECT key, reached_at FROM achievements")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    let stored: HashMap<String, DateTime<Utc>> = stored_rows
+        .iter()
+        .map(|row| (row.get("key"), row.get("reached_at")))
+        .collect();
+
+    for (key, reached_at) in &replay.reached {
+        let is_earlier = stored.get(key).is_none_or(|stored_at| reached_at < stored_at);
+        if is_earlier {
+            sqlx::query(
+                "INSERT INTO achievements (key, reached_at, created_at) VALUES (?, ?, ?)
+                ON CONFLICT(key) DO UPDATE SET reached_at = excluded.reached_at"
+            )
+            .bind(key)
+            .bind(reached_at)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        }
+    }
+
+    let earned_rows = sqlx::query("SELECT key, reached_at FROM achievements ORDER BY reached_at ASC")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let earned: Vec<EarnedAchievement> = earned_rows
+        .iter()
+        .filter_map(|row| {
+            let key: String = row.get("key");
+            describe(&key).map(|(title, category)| EarnedAchievement {
+                title: title.to_string(),
+                category: category.to_string(),
+                reached_at: row.get("reached_at"),
+                key,
+            })
+        })
+        .collect();
+    let is_earned = |key: &str| earned.iter().any(|achievement| achievement.key == key);
+
+    // The next unearned achievement of each kind
+    let mut upcoming = Vec::new();
+    if let Some((threshold, title)) = HOLDINGS_ACHIEVEMENTS
+        .iter()
+        .find(|(threshold, _)| !is_earned(&holdings_key(*threshold)))
+    {
+        let holdings = replay.holdings_sats.max(0);
+        upcoming.push(UpcomingAchievement {
+            key: holdings_key(*threshold),
+            title: title.to_string(),
+            category: "holdings".to_string(),
+            progress_percent: (holdings as f64 / *threshold as f64 * 100.0).min(100.0),
+            remaining: (threshold - holdings).max(0),
+            remaining_unit: "sats".to_string(),
+        });
+    }
+    if let Some((weeks, title)) = STREAK_ACHIEVEMENTS
+        .iter()
+        .find(|(weeks, _)| !is_earned(&streak_key(*weeks)))
+    {
+        upcoming.push(UpcomingAchievement {
+            key: streak_key(*weeks),
+            title: title.to_string(),
+            category: "streak".to_string(),
+            progress_percent: (replay.active_streak_weeks as f64 / *weeks as f64 * 100.0).min(100.0),
+            remaining: (weeks - replay.active_streak_weeks).max(0),
+            remaining_unit: "weeks".to_string(),
+        });
+    }
+    if let Some((days, title)) = HODL_ACHIEVEMENTS
+        .iter()
+        .find(|(days, _)| !is_earned(&hodl_key(*days)))
+    {
+        let held_days = replay.hodl_since.map_or(0, |since| (now - since).num_days());
+        upcoming.push(UpcomingAchievement {
+            key: hodl_key(*days),
+            title: title.to_string(),
+            category: "hodl".to_string(),
+            progress_percent: (held_days as f64 / *days as f64 * 100.0).min(100.0),
+            remaining: (days - held_days).max(0),
+            remaining_unit: "days".to_string(),
+        });
+    }
+
+    println!(
+        "Retrieved {} earned and {} upcoming achievements",
+        earned.len(),
+        upcoming.len()
+    );
+    Ok(AchievementsReport { earned, upcoming })
+}
@@ -1,99 +1,100 @@
-use crate::models::activity_metrics::{ActivityMetrics, YearHeatmapData, WeekData, DayData};
-use chrono::{Utc, DateTime, Datelike, Weekday, Duration, IsoWeek, TimeZone};
+use crate::commands::lightning_payment::msats_to_sats;
+use crate::commands::settings::{load_settings, parse_week_start};
+use crate::commands::tag::{tag_filter_json, tag_filter_sql};
+use crate::commands::time_buckets::parse_timezone;
+use crate::models::activity_metrics::{
+    ActivityMetrics, CadenceStreak, DayData, HeatmapMetric, StreakCadence, WeekData,
+    YearHeatmapData,
+};
+use chrono::{Utc, DateTime, Datelike, Weekday, Duration, NaiveDate};
 use sqlx::{SqlitePool, Row};
 use tauri::State;
 use std::collections::HashMap;
 
-fn calculate_heatmap_data(buy_transactions: &[sqlx::sqlite::SqliteRow]) -> Vec<YearHeatmapData> {
-    let mut years_data: HashMap<i32, Vec<(DateTime<Utc>, i64)>> = HashMap::new();
-    
-    // Group transactions by year
-    for row in buy_transactions {
-        let timestamp: DateTime<Utc> = row.get("timestamp");
-        let amount_sats: i64 = row.get("amount_sats");
-        let year = timestamp.year();
-        
-        years_data.entry(year).or_insert_with(Vec::new).push((timestamp, amount_sats));
-    }
+/// Per local day totals feeding the heatmap.
+#[derive(Default, Clone, Copy)]
+struct HeatmapDay {
+    sats: i64,
+    value: i64,
+}
+
+fn calculate_heatmap_data(
+    daily: &HashMap<NaiveDate, HeatmapDay>,
+    current_year: i32,
+    week_start: Weekday,
+    metric: HeatmapMetric,
+) -> Vec<YearHeatmapData> {
+    let mut years: Vec<i32> = daily.keys().map(|date| date.year()).collect();
     
     // Add current year if no transactions
-    let current_year = Utc::now().year();
-    if !years_data.contains_key(&current_year) {
-        years_data.insert(current_year, Vec::new());
-    }
-    
-    let mut result = Vec::new();
-    
-    for (year, transactions) in years_data {
-        let heatmap_data = generate_year_heatmap_data(year, &transactions);
-        result.push(heatmap_data);
-    }
+    years.push(current_year);
+    years.sort_unstable();
+    years.dedup();
     
     // Sort by year descending (most recent first)
-    result.sort_by(|a, b| b.year.cmp(&a.year));
-    
-    result
+    years
+        .into_iter()
+        .rev()
+        .map(|year| generate_year_heatmap_data(year, daily, week_start, metric))
+        .collect()
 }
 
-fn generate_year_heatmap_data(year: i32, transactions: &[(DateTime<Utc>, i64)]) -> YearHeatmapData {
-    // Calculate daily sats for this year
-    let mut daily_sats: HashMap<String, i64> = HashMap::new();
-    
-    for (timestamp, amount_sats) in transactions {
-        if timestamp.year() == year {
-            // Use local date components to avoid timezone issues
-            let date_key = format!("{:04}-{:02}-{:02}", 
-                timestamp.year(), 
-                timestamp.month(), 
-                timestamp.day()
-            );
-            *daily_sats.entry(date_key).or_insert(0) += amount_sats;
-        }
-    }
-    
-    // Find max sats for intensity calculation
-    let max_sats = daily_sats.values().max().copied().unwrap_or(1);
-    
-    // Generate 52 weeks of data
+fn generate_year_heatmap_data(
+    year: i32,
+    daily: &HashMap<NaiveDate, HeatmapDay>,
+    week_start: Weekday,
+    metric: HeatmapMetric,
+) -> YearHeatmapData {
+    let first_day = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    let last_day = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+    let in_year = |date: &NaiveDate| date.year() == year;
+
+    // Find max values for intensity calculation
+    let max_sats = daily
+        .iter()
+        .filter(|(date, _)| in_year(date))
+        .map(|(_, day)| day.sats)
+        .max()
+        .unwrap_or(1);
+    let max_value = daily
+        .iter()
+        .filter(|(date, _)| in_year(date))
+        .map(|(_, day)| day.value.abs())
+        .max()
+        .unwrap_or(1);
+
+    // Generate every week from the one holding January 1st to the one holding December 31st
     let mut weeks = Vec::new();
-    
-    // Start from January 1st of the year
-    let start_date = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
-    
-    // Find the first Sunday (or use Jan 1 if it's Sunday)
-    let mut first_sunday = start_date;
-    while first_sunday.weekday() != Weekday::Sun {
-        first_sunday = first_sunday - Duration::days(1);
-    }
-    
-    for week in 0..52 {
+    let mut week_first_day = week_start_date(first_day, week_start);
+    while week_first_day <= last_day {
         let mut week_data = WeekData { days: Vec::new() };
         
-        for day in 0..7 {
-            let current_date = first_sunday + Duration::weeks(week) + Duration::days(day);
-            let date_key = format!("{:04}-{:02}-{:02}", 
-                current_date.year(), 
-                current_date.month(), 
-                current_date.day()
-            );
-            
-            let sats = daily_sats.get(&date_key).copied().unwrap_or(0);
-            let level = get_intensity_level(sats, max_sats);
+        for current_date in week_first_day.iter_days().take(7) {
+            // Days of neighbouring years are padding only
+            let day = if in_year(&current_date) {
+                daily.get(&current_date).copied().unwrap_or_default()
+            } else {
+                HeatmapDay::default()
+            };
             
             week_data.days.push(DayData {
-                date: date_key,
-                sats,
-                level,
+                date: current_date.format("%Y-%m-%d").to_string(),
+                sats: day.sats,
+                level: get_intensity_level(day.value.abs(), max_value),
+                value: day.value,
             });
         }
         
         weeks.push(week_data);
+        week_first_day += Duration::days(7);
     }
     
     YearHeatmapData {
         year,
         weeks,
         max_sats,
+        metric,
+        max_value,
     }
 }
 
@@ -117,17 +118,70 @@ fn get_intensity_level(sats: i64, max_sats: i64) -> i32 {
     }
 }
 
+/// Every change in holdings in msats, for the net flow heatmap.
+async fn fetch_net_flows(
+    pool: &SqlitePool,
+    tag_filter: &Option<String>,
+) -> Result<Vec<(DateTime<Utc>, i64)>, String> {
+    let rows = sqlx::query(&format!(
+        "SELECT timestamp, CASE WHEN type = 'buy' THEN amount_sats ELSE -amount_sats END * 1000 as msats FROM exchange_transactions WHERE {}
+        UNION ALL
+        SELECT timestamp, -amount_sats * 1000 FROM onchain_fees WHERE {}
+        UNION ALL
+        SELECT timestamp, -amount_sats * 1000 FROM spending_events WHERE {}
+        UNION ALL
+        SELECT timestamp, amount_sats * 1000 FROM balance_adjustments WHERE {}
+        UNION ALL
+        SELECT timestamp, CASE WHEN direction = 'incoming' THEN amount_msats ELSE -amount_msats END - routing_fee_msats FROM lightning_payments WHERE {}",
+        tag_filter_sql("exchange_transactions", "'exchange_transaction'"),
+        tag_filter_sql("onchain_fees", "'onchain_fee'"),
+        tag_filter_sql("spending_events", "'spending'"),
+        tag_filter_sql("balance_adjustments", "'balance_adjustment'"),
+        tag_filter_sql("lightning_payments", "'lightning_payment'")
+    ))
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .bind(tag_filter)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("timestamp"), row.get("msats")))
+        .collect())
+}
+
 #[tauri::command]
 pub async fn get_activity_metrics(
     pool: State<'_, SqlitePool>,
+    tag_ids: Option<Vec<String>>,
+    timezone: Option<String>,
+    week_start: Option<String>,
+    heatmap_metric: Option<HeatmapMetric>,
 ) -> Result<ActivityMetrics, String> {
+    let heatmap_metric = heatmap_metric.unwrap_or_default();
     let now = Utc::now();
     let current_year = now.year();
+    let settings = load_settings(pool.inner()).await?;
+    let tz = parse_timezone(timezone.as_deref().or(settings.timezone.as_deref()))?;
+    let week_start = parse_week_start(week_start.as_deref().unwrap_or(&settings.week_start))?;
     
     // Get all buy transactions ordered by timestamp
-    let buy_transactions = sqlx::query(
-        "SELECT timestamp, amount_sats FROM exchange_transactions WHERE type = 'buy' ORDER BY timestamp ASC"
-    )
+    let tag_filter = tag_filter_json(tag_ids.as_deref());
+    let buy_transactions = sqlx::query(&format!(
+        "SELECT timestamp, amount_sats, COALESCE(subtotal_cents, 0) as subtotal_cents FROM exchange_transactions WHERE type = 'buy' AND {} ORDER BY timestamp ASC",
+        tag_filter_sql("exchange_transactions", "'exchange_transaction'")
+    ))
+    .bind(&tag_filter)
+    .bind(&tag_filter)
     .fetch_all(pool.inner())
     .await
     .map_err(|e| format!("Database error: {}", e))?;
@@ -144,6 +198,17 @@ pub async fn get_activity_metrics(
             weeks_to_next_milestone: None,
             next_milestone_description: None,
             heatmap_data: Vec::new(),
+            streaks: STREAK_CADENCES
+                .iter()
+                .map(|&cadence| CadenceStreak {
+                    cadence,
+                    current_streak: 0,
+                    longest_streak: 0,
+                })
+                .collect(),
+            primary_streak_cadence: settings.streak_cadence,
+            timezone: tz.name().to_string(),
+            week_start: week_start.to_string(),
         });
     }
 
@@ -163,8 +228,27 @@ pub async fn get_activity_metrics(
         }
     }
 
-    // Calculate streaks
-    let (current_streak, longest_streak) = calculate_weekly_streaks(&transaction_dates);
+    // Calculate streaks on local calendar dates
+    let local_dates: Vec<NaiveDate> = transaction_dates
+        .iter()
+        .map(|date| date.with_timezone(&tz).date_naive())
+        .collect();
+    let today = now.with_timezone(&tz).date_naive();
+    let streaks: Vec<CadenceStreak> = STREAK_CADENCES
+        .iter()
+        .map(|&cadence| {
+            let (current_streak, longest_streak) =
+                calculate_streaks(&local_dates, cadence, week_start, today);
+            CadenceStreak {
+                cadence,
+                current_streak,
+                longest_streak,
+            }
+        })
+        .collect();
+    // The single-streak fields follow the cadence chosen in settings
+    let (current_streak, longest_streak) =
+        calculate_streaks(&local_dates, settings.streak_cadence, week_start, today);
 
     // Calculate best stacking day
     let (best_day, best_day_percentage) = calculate_best_stacking_day(&transaction_dates);
@@ -181,10 +265,42 @@ pub async fn get_activity_metrics(
     }.to_string();
 
     // Calculate next milestone
-    let (weeks_to_milestone, milestone_desc) = calculate_next_weekly_milestone(current_streak);
+    let (weeks_to_milestone, milestone_desc) =
+        calculate_next_milestone(current_streak, settings.streak_cadence);
 
-    // Calculate heatmap data
-    let heatmap_data = calculate_heatmap_data(&buy_transactions);
+    // Calculate heatmap data on local calendar days
+    let mut heatmap_days: HashMap<NaiveDate, HeatmapDay> = HashMap::new();
+    for row in &buy_transactions {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let amount_sats: i64 = row.get("amount_sats");
+        let day = heatmap_days
+            .entry(timestamp.with_timezone(&tz).date_naive())
+            .or_default();
+        day.sats += amount_sats;
+        day.value += match heatmap_metric {
+            HeatmapMetric::Sats => amount_sats,
+            HeatmapMetric::Fiat => row.get::<i64, _>("subtotal_cents"),
+            HeatmapMetric::Buys => 1,
+            HeatmapMetric::NetFlow => 0,
+        };
+    }
+    if heatmap_metric == HeatmapMetric::NetFlow {
+        let mut net_msats: HashMap<NaiveDate, i64> = HashMap::new();
+        for (timestamp, msats) in fetch_net_flows(pool.inner(), &tag_filter).await? {
+            *net_msats
+                .entry(timestamp.with_timezone(&tz).date_naive())
+                .or_insert(0) += msats;
+        }
+        for (date, msats) in net_msats {
+            heatmap_days.entry(date).or_default().value = msats_to_sats(msats);
+        }
+    }
+    let heatmap_data = calculate_heatmap_data(
+        &heatmap_days,
+        now.with_timezone(&tz).year(),
+        week_start,
+        heatmap_metric,
+    );
 
     Ok(ActivityMetrics {
         current_streak_weeks: current_streak,
@@ -197,75 +313,78 @@ pub async fn get_activity_metrics(
         weeks_to_next_milestone: weeks_to_milestone,
         next_milestone_description: milestone_desc,
         heatmap_data,
+        streaks,
+        primary_streak_cadence: settings.streak_cadence,
+        timezone: tz.name().to_string(),
+        week_start: week_start.to_string(),
     })
 }
 
-fn calculate_weekly_streaks(transaction_dates: &[DateTime<Utc>]) -> (i32, i32) {
-    if transaction_dates.is_empty() {
-        return (0, 0);
-    }
-
-    // Group transactions by ISO week (year + week number)
-    let mut unique_weeks: Vec<IsoWeek> = transaction_dates
-        .iter()
-        .map(|dt| dt.iso_week())
-        .collect();
-    unique_weeks.sort_by_key(|week| (week.year(), week.week()));
-    unique_weeks.dedup();
-
-    let mut current_streak = 0;
-    let mut longest_streak = 0;
-    let mut temp_streak = 1;
+const STREAK_CADENCES: [StreakCadence; 4] = [
+    StreakCadence::Daily,
+    StreakCadence::Weekly,
+    StreakCadence::Biweekly,
+    StreakCadence::Monthly,
+];
+
+/// First day of the week containing `date`, for weeks starting on `week_start`.
+pub(crate) fn week_start_date(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let days_into_week = (date.weekday().num_days_from_monday() + 7
+        - week_start.num_days_from_monday())
+        % 7;
+    date - Duration::days(days_into_week as i64)
+}
 
-    let current_week = Utc::now().iso_week();
-    
-    // Check if current streak is active (last purchase within current or previous week)
-    if let Some(last_week) = unique_weeks.last() {
-        let weeks_since_last = week_difference(&current_week, last_week);
-        
-        if weeks_since_last <= 1 {
-            current_streak = 1;
-            
-            // Calculate current streak backwards from the most recent week
-            for i in (0..unique_weeks.len().saturating_sub(1)).rev() {
-                let current_week_item = unique_weeks[i];
-                let next_week_item = unique_weeks[i + 1];
-                let gap = week_difference(&next_week_item, &current_week_item);
-                
-                if gap <= 1 {
-                    current_streak += 1;
-                } else {
-                    break;
-                }
-            }
+/// Sequential number of the period containing `date`, so consecutive periods differ by one
+/// regardless of month lengths or 53-week years.
+pub(crate) fn period_index(date: NaiveDate, cadence: StreakCadence, week_start: Weekday, anchor: NaiveDate) -> i64 {
+    match cadence {
+        StreakCadence::Daily => date.num_days_from_ce() as i64,
+        StreakCadence::Weekly => (week_start_date(date, week_start).num_days_from_ce() as i64).div_euclid(7),
+        StreakCadence::Biweekly => {
+            let weeks_since_anchor = (week_start_date(date, week_start) - week_start_date(anchor, week_start)).num_days() / 7;
+            weeks_since_anchor.div_euclid(2)
         }
+        StreakCadence::Monthly => date.year() as i64 * 12 + date.month0() as i64,
     }
+}
 
-    // Calculate longest streak
-    for i in 1..unique_weeks.len() {
-        let prev_week = unique_weeks[i - 1];
-        let current_week_item = unique_weeks[i];
-        let gap = week_difference(&current_week_item, &prev_week);
-        
-        if gap <= 1 {
-            temp_streak += 1;
+/// Current and longest runs of consecutive periods with at least one buy. The current streak
+/// stays active while the last buy falls in the current or the previous period.
+pub(crate) fn calculate_streaks(
+    dates: &[NaiveDate],
+    cadence: StreakCadence,
+    week_start: Weekday,
+    today: NaiveDate,
+) -> (i32, i32) {
+    let Some(anchor) = dates.iter().min().copied() else {
+        return (0, 0);
+    };
+
+    let mut periods: Vec<i64> = dates
+        .iter()
+        .map(|date| period_index(*date, cadence, week_start, anchor))
+        .collect();
+    periods.sort_unstable();
+    periods.dedup();
+
+    let mut longest_streak = 1;
+    let mut run = 1;
+    for pair in periods.windows(2) {
+        if pair[1] - pair[0] == 1 {
+            run += 1;
+            longest_streak = longest_streak.max(run);
         } else {
-            longest_streak = longest_streak.max(temp_streak);
-            temp_streak = 1;
+            run = 1;
         }
     }
-    longest_streak = longest_streak.max(temp_streak);
 
-    (current_streak, longest_streak)
-}
+    // `run` now holds the streak ending at the most recent period
+    let current_period = period_index(today, cadence, week_start, anchor);
+    let last_period = *periods.last().unwrap();
+    let current_streak = if current_period - last_period <= 1 { run } else { 0 };
 
-fn week_difference(week1: &IsoWeek, week2: &IsoWeek) -> i32 {
-    // Calculate the difference in weeks between two ISO weeks
-    let year_diff = week1.year() - week2.year();
-    let week_diff = week1.week() as i32 - week2.week() as i32;
-    
-    // Approximate calculation (assumes 52 weeks per year)
-    year_diff * 52 + week_diff
+    (current_streak, longest_streak)
 }
 
 fn calculate_best_stacking_day(transaction_dates: &[DateTime<Utc>]) -> (Option<String>, f64) {
@@ -367,35 +486,109 @@ fn calculate_weekly_consistency_score(transaction_dates: &[DateTime<Utc>]) -> f6
     (weighted_score / total_weight) * 100.0
 }
 
-fn calculate_next_weekly_milestone(current_streak: i32) -> (Option<i32>, Option<String>) {
-    let milestones = [4, 8, 12, 26, 52]; // 1 month, 2 months, 3 months, 6 months, 1 year
-    
-    for &milestone in &milestones {
-        if current_streak < milestone {
-            let weeks_to_go = milestone - current_streak;
-            let description = match milestone {
-                4 => "1-month streak".to_string(),
-                8 => "2-month streak".to_string(),
-                12 => "3-month streak".to_string(),
-                26 => "6-month streak".to_string(),
-                52 => "1-year streak".to_string(),
-                _ => format!("{}-week streak", milestone),
-            };
-            return (Some(weeks_to_go), Some(description));
-        }
+/// Streak lengths worth celebrating for `cadence` with their descriptions, and the number of
+/// periods in a year for the yearly milestones after them.
+fn streak_milestones(cadence: StreakCadence) -> (&'static [(i32, &'static str)], i32) {
+    match cadence {
+        StreakCadence::Daily => (
+            &[(7, "1-week streak"), (30, "1-month streak"), (90, "3-month streak"), (180, "6-month streak"), (365, "1-year streak")],
+            365,
+        ),
+        StreakCadence::Weekly => (
+            &[(4, "1-month streak"), (8, "2-month streak"), (12, "3-month streak"), (26, "6-month streak"), (52, "1-year streak")],
+            52,
+        ),
+        StreakCadence::Biweekly => (
+            &[(2, "1-month streak"), (6, "3-month streak"), (13, "6-month streak"), (26, "1-year streak")],
+            26,
+        ),
+        StreakCadence::Monthly => (
+            &[(3, "3-month streak"), (6, "6-month streak"), (12, "1-year streak")],
+            12,
+        ),
     }
-    
-    // If past all initial milestones, calculate next yearly milestone
-    if current_streak >= 52 {
-        let current_year = (current_streak / 52) + 1;
-        let next_year = current_year + 1;
-        let weeks_into_current_year = current_streak % 52;
-        let weeks_to_next_year = 52 - weeks_into_current_year;
-        
-        let description = format!("{}-year streak", next_year);
-        return (Some(weeks_to_next_year), Some(description));
+}
+
+/// Periods of `cadence` until the next streak milestone, and its description.
+fn calculate_next_milestone(current_streak: i32, cadence: StreakCadence) -> (Option<i32>, Option<String>) {
+    let (milestones, periods_per_year) = streak_milestones(cadence);
+
+    if let Some((milestone, description)) = milestones.iter().find(|(milestone, _)| current_streak < *milestone) {
+        return (Some(milestone - current_streak), Some(description.to_string()));
+    }
+
+    // Past the first year, every further year is the next milestone
+    let next_year = current_streak / periods_per_year + 1;
+    (
+        Some(next_year * periods_per_year - current_streak),
+        Some(format!("{}-year streak", next_year)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn weekly_streak_spans_a_53_week_year() {
+        // 2020 has 53 ISO weeks; one buy every Monday from its first full week into 2021
+        let dates: Vec<NaiveDate> = date(2020, 1, 6)
+            .iter_weeks()
+            .take_while(|day| *day <= date(2021, 1, 11))
+            .collect();
+        assert_eq!(dates.len(), 54);
+
+        let streaks = calculate_streaks(&dates, StreakCadence::Weekly, Weekday::Mon, date(2021, 1, 17));
+        assert_eq!(streaks, (54, 54));
+
+        // The streak survives the week after the last buy, not the one after that
+        let streaks = calculate_streaks(&dates, StreakCadence::Weekly, Weekday::Mon, date(2021, 1, 24));
+        assert_eq!(streaks, (54, 54));
+        let streaks = calculate_streaks(&dates, StreakCadence::Weekly, Weekday::Mon, date(2021, 1, 25));
+        assert_eq!(streaks, (0, 54));
+    }
+
+    #[test]
+    fn weekly_periods_follow_the_week_start() {
+        let saturday = date(2021, 1, 2);
+        let sunday = date(2021, 1, 3);
+        let period = |date, week_start| period_index(date, StreakCadence::Weekly, week_start, date);
+
+        assert_eq!(period(sunday, Weekday::Mon), period(saturday, Weekday::Mon));
+        assert_eq!(period(sunday, Weekday::Sun), period(saturday, Weekday::Sun) + 1);
+    }
+
+    #[test]
+    fn monthly_streak_crosses_the_year_and_short_months() {
+        let dates = [date(2021, 11, 30), date(2021, 12, 31), date(2022, 1, 1), date(2022, 2, 28)];
+
+        let streaks = calculate_streaks(&dates, StreakCadence::Monthly, Weekday::Mon, date(2022, 3, 31));
+        assert_eq!(streaks, (4, 4));
+        let streaks = calculate_streaks(&dates, StreakCadence::Monthly, Weekday::Mon, date(2022, 4, 1));
+        assert_eq!(streaks, (0, 4));
+    }
+
+    #[test]
+    fn milestones_count_periods_of_the_cadence() {
+        assert_eq!(
+            calculate_next_milestone(3, StreakCadence::Weekly),
+            (Some(1), Some("1-month streak".to_string()))
+        );
+        assert_eq!(
+            calculate_next_milestone(52, StreakCadence::Weekly),
+            (Some(52), Some("2-year streak".to_string()))
+        );
+        assert_eq!(
+            calculate_next_milestone(7, StreakCadence::Monthly),
+            (Some(5), Some("1-year streak".to_string()))
+        );
+        assert_eq!(
+            calculate_next_milestone(12, StreakCadence::Monthly),
+            (Some(12), Some("2-year streak".to_string()))
+        );
     }
-    
-    // Fallback (shouldn't reach here)
-    (Some(52 - (current_streak % 52)), Some("Next yearly milestone".to_string()))
 }
@@ -1,17 +1,71 @@
+use crate::commands::price_provider::{load_cached_price, provider_chain, save_cached_price};
+use crate::commands::settings::load_settings;
+use crate::models::settings::AppSettings;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::sync::Mutex;
+use tauri::State;
+
+pub(crate) const OFFLINE_MODE_ERROR: &str = "Offline mode is enabled: network access is disabled";
+
+/// Built-in API host, overridable at runtime with the `SAT_TRACKER_API_HOST` environment variable.
+pub(crate) fn default_api_host() -> String {
+    if let Ok(host) = std::env::var("SAT_TRACKER_API_HOST") {
+        if !host.trim().is_empty() {
+            return host.trim().trim_end_matches('/').to_string();
+        }
+    }
 
-fn get_api_host() -> &'static str {
     #[cfg(debug_assertions)]
     {
         // Development: use localhost
-        "http://localhost:3000"
+        "http://localhost:3000".to_string()
     }
     
     #[cfg(not(debug_assertions))]
     {
         // Production: use production API
-        "https://dprogram.me"
+        "https://dprogram.me".to_string()
+    }
+}
+
+/// The client shared by every command, with the proxy URL it was built for.
+static HTTP_CLIENT: Mutex<Option<(Option<String>, reqwest::Client)>> = Mutex::new(None);
+
+/// The only way to get an HTTP client, so offline mode and the proxy setting hold for every
+/// network call. Fails with `OFFLINE_MODE_ERROR` before any request can be made in offline mode.
+///
+/// The client is built once and shared, and only rebuilt when the proxy setting changes. With a
+/// proxy configured every request goes through it; there is no direct fallback.
+pub(crate) fn http_client(settings: &AppSettings) -> Result<reqwest::Client, String> {
+    if settings.offline_mode {
+        return Err(OFFLINE_MODE_ERROR.to_string());
+    }
+
+    let mut shared = HTTP_CLIENT
+        .lock()
+        .map_err(|_| "HTTP client lock poisoned".to_string())?;
+    if let Some((proxy_url, client)) = shared.as_ref() {
+        if *proxy_url == settings.proxy_url {
+            return Ok(client.clone());
+        }
+    }
+
+    let mut builder = reqwest::Client::builder()
+        .user_agent(format!("SatTracker/{}", env!("CARGO_PKG_VERSION")));
+    if let Some(proxy_url) = &settings.proxy_url {
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        builder = builder.proxy(proxy);
     }
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    println!("Built HTTP client (proxy: {})", settings.proxy_url.as_deref().unwrap_or("none"));
+    *shared = Some((settings.proxy_url.clone(), client.clone()));
+    Ok(client)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,50 +81,105 @@ pub struct BitcoinPriceResponse {
     pub stale: Option<bool>,
     pub message: Option<String>,
     pub error: Option<String>,
+    pub source: Option<String>, // price provider that produced the quote
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnnouncementsResponse {
     pub latest_version: String,
     pub announcements: Vec<String>,
+    pub stale: Option<bool>, // true when served from the cache
+    pub message: Option<String>, // why the cache was served, e.g. offline mode
 }
 
+/// Current BTC price from the first price provider in the settings chain that answers.
+///
+/// Each provider is retried with exponential backoff before moving on to the next. Good quotes
+/// are persisted, and when every provider fails the last persisted quote is returned marked as
+/// stale rather than failing outright.
 #[tauri::command]
-pub async fn fetch_bitcoin_price() -> Result<BitcoinPriceResponse, String> {
-    let client = reqwest::Client::builder()
-        .user_agent(format!("SatTracker/{}", env!("CARGO_PKG_VERSION")))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+pub async fn fetch_bitcoin_price(
+    pool: State<'_, SqlitePool>,
+) -> Result<BitcoinPriceResponse, String> {
+    let settings = load_settings(pool.inner()).await?;
+    let currency = settings.base_currency.as_str();
 
-    let url = format!("{}/api/proxy/sat-tracker/bitcoin-price", get_api_host());
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Bitcoin price: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
+    let mut errors = Vec::new();
+    for provider in provider_chain(&settings) {
+        match provider.fetch_price_with_retries(currency).await {
+            Ok(price_data) => {
+                save_cached_price(pool.inner(), currency, provider.name(), &price_data).await?;
+                println!("Fetched price from {}: {:?}", provider.name(), price_data);
+                return Ok(price_data);
+            }
+            Err(e) => {
+                println!("Price provider {} failed: {}", provider.name(), e);
+                errors.push(format!("{}: {}", provider.name(), e));
+            }
+        }
     }
 
-    let price_data: BitcoinPriceResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    if settings.offline_mode {
+        errors.insert(0, OFFLINE_MODE_ERROR.to_string());
+    }
+    let failure = if errors.is_empty() {
+        "No price provider is available with the current settings".to_string()
+    } else {
+        errors.join("; ")
+    };
 
-    println!("{:?}", price_data);
+    match load_cached_price(pool.inner(), currency).await? {
+        Some(mut cached) => {
+            cached.message = Some(failure);
+            println!("Serving cached price: {:?}", cached);
+            Ok(cached)
+        }
+        None => Err(failure),
+    }
+}
 
-    Ok(price_data)
+/// The last persisted quote in the base currency, without touching the network. Lets the UI
+/// show a known price immediately after a restart.
+#[tauri::command]
+pub async fn get_cached_bitcoin_price(
+    pool: State<'_, SqlitePool>,
+) -> Result<Option<BitcoinPriceResponse>, String> {
+    let settings = load_settings(pool.inner()).await?;
+    load_cached_price(pool.inner(), &settings.base_currency).await
 }
 
+/// Latest announcements from the API host. Successful responses are persisted, and the last
+/// persisted ones are served when offline mode is on or the request fails, marked as stale with
+/// the reason in `message`.
 #[tauri::command]
-pub async fn fetch_announcements() -> Result<AnnouncementsResponse, String> {
-    let client = reqwest::Client::builder()
-        .user_agent(format!("SatTracker/{}", env!("CARGO_PKG_VERSION")))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+pub async fn fetch_announcements(
+    pool: State<'_, SqlitePool>,
+) -> Result<AnnouncementsResponse, String> {
+    let settings = load_settings(pool.inner()).await?;
+
+    match request_announcements(&settings).await {
+        Ok(mut announcements_data) => {
+            save_cached_announcements(pool.inner(), &announcements_data).await?;
+            announcements_data.stale = Some(false);
+            Ok(announcements_data)
+        }
+        Err(e) => match load_cached_announcements(pool.inner()).await? {
+            Some((mut announcements_data, fetched_at)) => {
+                println!("Serving announcements cached at {}: {}", fetched_at, e);
+                announcements_data.stale = Some(true);
+                announcements_data.message = Some(e);
+                Ok(announcements_data)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+async fn request_announcements(settings: &AppSettings) -> Result<AnnouncementsResponse, String> {
+    let client = http_client(settings)?;
+    let api_host = settings.price_api_host.clone().unwrap_or_else(default_api_host);
 
-    let url = format!("{}/api/proxy/sat-tracker/announcements", get_api_host());
+    let url = format!("{}/api/proxy/sat-tracker/announcements", api_host);
     let response = client
         .get(&url)
         .send()
@@ -88,3 +197,50 @@ pub async fn fetch_announcements() -> Result<AnnouncementsResponse, String> {
 
     Ok(announcements_data)
 }
+
+async fn save_cached_announcements(
+    pool: &SqlitePool,
+    announcements_data: &AnnouncementsResponse,
+) -> Result<(), String> {
+    let announcements = serde_json::to_string(&announcements_data.announcements)
+        .map_err(|e| format!("Failed to serialize announcements: {}", e))?;
+
+    sqlx::query(
+        "INSERT INTO announcements_cache (id, latest_version, announcements, fetched_at) VALUES (1, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET latest_version = excluded.latest_version,
+            announcements = excluded.announcements, fetched_at = excluded.fetched_at"
+    )
+    .bind(&announcements_data.latest_version)
+    .bind(announcements)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+async fn load_cached_announcements(
+    pool: &SqlitePool,
+) -> Result<Option<(AnnouncementsResponse, DateTime<Utc>)>, String> {
+    let row = sqlx::query(
+        "SELECT latest_version, announcements, fetched_at FROM announcements_cache WHERE id = 1"
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let announcements: String = row.get("announcements");
+    let announcements_data = AnnouncementsResponse {
+        latest_version: row.get("latest_version"),
+        announcements: serde_json::from_str(&announcements)
+            .map_err(|e| format!("Failed to parse cached announcements: {}", e))?,
+        stale: None,
+        message: None,
+    };
+    Ok(Some((announcements_data, row.get("fetched_at"))))
+}
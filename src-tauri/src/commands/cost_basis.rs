@@ -0,0 +1,172 @@
+use crate::commands::price_history::{load_daily_closes, price_at};
+use crate::commands::settings::load_settings;
+use crate::commands::tag::{tag_filter_json, tag_filter_sql};
+use crate::models::settings::CostBasisMethod;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::VecDeque;
+
+/// Result of replaying every event against the holdings' cost basis.
+#[derive(Debug, Default)]
+pub(crate) struct RealizedGains {
+    pub proceeds_cents: i64,
+    pub cost_basis_disposed_cents: i64,
+    pub realized_gain_cents: i64,
+    pub remaining_cost_basis_cents: i64,
+    pub unpriced_disposal_count: i64, // spending without a fiat value or a stored price
+}
+
+/// Replays all events chronologically using the cost-basis method from settings: a single
+/// average-cost pool, or individual purchase lots consumed oldest first (FIFO) or newest first
+/// (LIFO).
+///
+/// Sells and spending events are disposals: their fiat value is compared with the average cost
+/// of the sats that left the stack. Spending without a recorded fiat value is valued at the
+/// stored price of its day. Fees, outgoing lightning payments, negative adjustments and spending
+/// that has no price either also remove sats (and their share of the cost basis) but realize no
+/// gain; the latter are counted in `unpriced_disposal_count`. Incoming lightning payments and
+/// positive adjustments add sats at zero cost. With `tag_ids` only events carrying
+/// any of those tags are replayed.
+///
+/// Events are replayed up to `end`, but only disposals from `start` on count as realized, so the
+/// cost basis of a period still reflects every earlier purchase.
+pub(crate) async fn calculate_realized_gains(
+    pool: &SqlitePool,
+    tag_ids: Option<&[String]>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<RealizedGains, String> {
+    let tag_filter = tag_filter_json(tag_ids);
+
+    let sql = format!(
+        r#"
+        SELECT * FROM (
+            SELECT timestamp, 'acquisition' as kind, amount_sats, COALESCE(subtotal_cents, 0) + COALESCE(fee_cents, 0) as fiat_cents
+            FROM exchange_transactions WHERE type = 'buy' AND {exchange_filter}
+            UNION ALL
+            SELECT timestamp, 'disposal' as kind, amount_sats, COALESCE(subtotal_cents, 0) - COALESCE(fee_cents, 0) as fiat_cents
+            FROM exchange_transactions WHERE type = 'sell' AND {exchange_filter}
+            UNION ALL
+            SELECT timestamp, 'disposal' as kind, amount_sats, fiat_value_cents as fiat_cents
+            FROM spending_events WHERE {spending_filter}
+            UNION ALL
+            SELECT timestamp, 'outflow' as kind, amount_sats, 0 as fiat_cents
+            FROM onchain_fees WHERE {onchain_fee_filter}
+            UNION ALL
+            SELECT timestamp,
+                CASE WHEN direction = 'incoming' THEN 'acquisition' ELSE 'outflow' END as kind,
+                CASE WHEN direction = 'incoming' THEN (amount_msats - routing_fee_msats) / 1000 ELSE (amount_msats + routing_fee_msats) / 1000 END as amount_sats,
+                0 as fiat_cents
+            FROM lightning_payments WHERE {lightning_filter}
+            UNION ALL
+            SELECT timestamp,
+                CASE WHEN amount_sats >= 0 THEN 'acquisition' ELSE 'outflow' END as kind,
+                ABS(amount_sats) as amount_sats,
+                0 as fiat_cents
+            FROM balance_adjustments WHERE {adjustment_filter}
+        ) AS events
+        WHERE (? IS NULL OR events.timestamp <= ?)
+        ORDER BY events.timestamp ASC
+        "#,
+        exchange_filter = tag_filter_sql("exchange_transactions", "'exchange_transaction'"),
+        spending_filter = tag_filter_sql("spending_events", "'spending'"),
+        onchain_fee_filter = tag_filter_sql("onchain_fees", "'onchain_fee'"),
+        lightning_filter = tag_filter_sql("lightning_payments", "'lightning_payment'"),
+        adjustment_filter = tag_filter_sql("balance_adjustments", "'balance_adjustment'"),
+    );
+    let mut query = sqlx::query(&sql);
+    // Each of the six branches binds the filter twice
+    for _ in 0..12 {
+        query = query.bind(&tag_filter);
+    }
+
+    let rows = query
+        .bind(end)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let method = load_settings(pool).await?.cost_basis_method;
+    let closes = load_daily_closes(pool, None, end.map(|end| end.date_naive())).await?;
+
+    let mut gains = RealizedGains::default();
+    // Purchase lots of (sats, cost), oldest first. The average method keeps a single lot.
+    let mut lots: VecDeque<(i64, f64)> = VecDeque::new();
+
+    for row in rows {
+        let kind: String = row.get("kind");
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let amount_sats: i64 = row.get("amount_sats");
+        let fiat_cents: Option<i64> = row.get("fiat_cents");
+        let in_period = start.is_none_or(|start| timestamp >= start);
+
+        let fiat_cents = match fiat_cents {
+            Some(fiat_cents) => Some(fiat_cents),
+            None => price_at(&closes, timestamp)
+                .map(|price| (amount_sats as f64 * price as f64 / 100_000_000.0).round() as i64),
+        };
+        let (kind, fiat_cents) = match fiat_cents {
+            Some(fiat_cents) => (kind.as_str(), fiat_cents),
+            None => {
+                if in_period {
+                    gains.unpriced_disposal_count += 1;
+                }
+                ("outflow", 0)
+            }
+        };
+
+        if kind == "acquisition" {
+            match (method, lots.back_mut()) {
+                (CostBasisMethod::Average, Some(pool_lot)) => {
+                    pool_lot.0 += amount_sats;
+                    pool_lot.1 += fiat_cents as f64;
+                }
+                _ => lots.push_back((amount_sats, fiat_cents as f64)),
+            }
+            continue;
+        }
+
+        // Outflows beyond the tracked holdings have no cost basis to remove
+        let mut remaining_sats = amount_sats.max(0);
+        let mut removed_cost = 0.0;
+        while remaining_sats > 0 {
+            let lot = match method {
+                CostBasisMethod::Lifo => lots.back_mut(),
+                CostBasisMethod::Average | CostBasisMethod::Fifo => lots.front_mut(),
+            };
+            let Some(lot) = lot else {
+                break;
+            };
+
+            let taken_sats = remaining_sats.min(lot.0);
+            let taken_cost = if lot.0 > 0 {
+                lot.1 * taken_sats as f64 / lot.0 as f64
+            } else {
+                0.0
+            };
+            lot.0 -= taken_sats;
+            lot.1 -= taken_cost;
+            remaining_sats -= taken_sats;
+            removed_cost += taken_cost;
+
+            if lot.0 <= 0 {
+                match method {
+                    CostBasisMethod::Lifo => lots.pop_back(),
+                    CostBasisMethod::Average | CostBasisMethod::Fifo => lots.pop_front(),
+                };
+            }
+        }
+
+        if kind == "disposal" && in_period {
+            gains.proceeds_cents += fiat_cents;
+            gains.cost_basis_disposed_cents += removed_cost.round() as i64;
+        }
+    }
+
+    let cost_basis_cents: f64 = lots.iter().map(|(_, cost)| cost).sum();
+    gains.realized_gain_cents = gains.proceeds_cents - gains.cost_basis_disposed_cents;
+    gains.remaining_cost_basis_cents = cost_basis_cents.round() as i64;
+
+    Ok(gains)
+}
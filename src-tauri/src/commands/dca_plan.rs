@@ -0,0 +1,458 @@
+use crate::commands::time_buckets::{local_midnight_utc, parse_timezone};
+use crate::commands::unified_events::UNIFIED_EVENTS_SQL;
+use crate::models::dca_plan::{
+    CreateDcaPlanRequest, DcaPlan, DcaPlanAdherence, PlanExtraBuy, PlanFrequency, PlanOccurrence,
+    UpdateDcaPlanRequest,
+};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+use uuid::Uuid;
+
+const DEFAULT_GRACE_DAYS: i64 = 2;
+/// The longest plan period; the next occurrence always cuts a grace window shorter than that.
+const MAX_GRACE_DAYS: i64 = 31;
+
+const PLAN_COLUMNS: &str = "id, name, amount_cents, frequency, day_of_week, day_of_month, provider, account_id, timezone, grace_days, start_date, end_date, created_at";
+
+fn dca_plan_from_row(row: &SqliteRow) -> Result<DcaPlan, String> {
+    Ok(DcaPlan {
+        id: row.get("id"),
+        name: row.get("name"),
+        amount_cents: row.get("amount_cents"),
+        frequency: row
+            .get::<String, _>("frequency")
+            .parse()
+            .map_err(|e| format!("Invalid plan frequency: {}", e))?,
+        day_of_week: row.get::<Option<i64>, _>("day_of_week").map(|day| day as u32),
+        day_of_month: row.get::<Option<i64>, _>("day_of_month").map(|day| day as u32),
+        provider: row.get("provider"),
+        account_id: row.get("account_id"),
+        timezone: row.get("timezone"),
+        grace_days: row.get("grace_days"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        created_at: row.get("created_at"),
+    })
+}
+
+fn validate_plan(plan: &DcaPlan) -> Result<(), String> {
+    if plan.name.is_empty() {
+        return Err("Plan name cannot be empty".to_string());
+    }
+    if plan.amount_cents <= 0 {
+        return Err("Plan amount must be positive".to_string());
+    }
+    if plan.day_of_week.is_some_and(|day| day > 6) {
+        return Err("Day of week must be between 0 (Monday) and 6 (Sunday)".to_string());
+    }
+    if plan.day_of_month.is_some_and(|day| !(1..=31).contains(&day)) {
+        return Err("Day of month must be between 1 and 31".to_string());
+    }
+    if !(0..=MAX_GRACE_DAYS).contains(&plan.grace_days) {
+        return Err(format!("Grace days must be between 0 and {}", MAX_GRACE_DAYS));
+    }
+    if plan.end_date.is_some_and(|end| end <= plan.start_date) {
+        return Err("End date must be after the start date".to_string());
+    }
+    parse_timezone(plan.timezone.as_deref())?;
+    Ok(())
+}
+
+/// `day` of the month starting at `month_start`, clamped to the month's last day.
+fn clamped_day_of_month(month_start: NaiveDate, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|d| month_start.with_day(d))
+        .unwrap_or(month_start)
+}
+
+/// Scheduled local dates of `plan` from `first_day` through `last_day`.
+fn scheduled_dates(plan: &DcaPlan, first_day: NaiveDate, last_day: NaiveDate) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    match plan.frequency {
+        PlanFrequency::Daily => {
+            dates.extend(first_day.iter_days().take_while(|day| *day <= last_day));
+        }
+        PlanFrequency::Weekly | PlanFrequency::Biweekly => {
+            let weekday = plan
+                .day_of_week
+                .unwrap_or_else(|| first_day.weekday().num_days_from_monday());
+            let offset = (weekday + 7 - first_day.weekday().num_days_from_monday()) % 7;
+            let step = if plan.frequency == PlanFrequency::Weekly { 7 } else { 14 };
+            let first = first_day + Days::new(offset as u64);
+            dates.extend(
+                first
+                    .iter_days()
+                    .step_by(step)
+                    .take_while(|day| *day <= last_day),
+            );
+        }
+        PlanFrequency::Monthly => {
+            let day_of_month = plan.day_of_month.unwrap_or_else(|| first_day.day());
+            let first_month = first_day.with_day(1).unwrap();
+            for month in 0.. {
+                let Some(month_start) = first_month.checked_add_months(Months::new(month)) else {
+                    break;
+                };
+                if month_start > last_day {
+                    break;
+                }
+                let date = clamped_day_of_month(month_start, day_of_month);
+                if date >= first_day && date <= last_day {
+                    dates.push(date);
+                }
+            }
+        }
+    }
+    dates
+}
+
+#[tauri::command]
+pub async fn create_dca_plan(
+    pool: State<'_, SqlitePool>,
+    request: CreateDcaPlanRequest,
+) -> Result<DcaPlan, String> {
+    let plan = DcaPlan {
+        id: Uuid::new_v4().to_string(),
+        name: request.name.trim().to_string(),
+        amount_cents: request.amount_cents,
+        frequency: request.frequency,
+        day_of_week: request.day_of_week,
+        day_of_month: request.day_of_month,
+        provider: request.provider,
+        account_id: request.account_id,
+        timezone: request.timezone,
+        grace_days: request.grace_days.unwrap_or(DEFAULT_GRACE_DAYS),
+        start_date: request.start_date,
+        end_date: request.end_date,
+        created_at: Utc::now(),
+    };
+    validate_plan(&plan)?;
+
+    sqlx::query(&format!(
+        "INSERT INTO dca_plans ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        PLAN_COLUMNS
+    ))
+    .bind(&plan.id)
+    .bind(&plan.name)
+    .bind(plan.amount_cents)
+    .bind(plan.frequency.to_string())
+    .bind(plan.day_of_week.map(|day| day as i64))
+    .bind(plan.day_of_month.map(|day| day as i64))
+    .bind(&plan.provider)
+    .bind(&plan.account_id)
+    .bind(&plan.timezone)
+    .bind(plan.grace_days)
+    .bind(plan.start_date)
+    .bind(plan.end_date)
+    .bind(plan.created_at)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Created DCA plan: {:?}", plan);
+    Ok(plan)
+}
+
+#[tauri::command]
+pub async fn get_dca_plans(pool: State<'_, SqlitePool>) -> Result<Vec<DcaPlan>, String> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM dca_plans ORDER BY created_at ASC",
+        PLAN_COLUMNS
+    ))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let plans = rows
+        .iter()
+        .map(dca_plan_from_row)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    println!("Retrieved {} DCA plans", plans.len());
+    Ok(plans)
+}
+
+async fn fetch_dca_plan(pool: &SqlitePool, id: &str) -> Result<DcaPlan, String> {
+    let row = sqlx::query(&format!("SELECT {} FROM dca_plans WHERE id = ?", PLAN_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "DCA plan not found".to_string())?;
+
+    dca_plan_from_row(&row)
+}
+
+#[tauri::command]
+pub async fn update_dca_plan(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    request: UpdateDcaPlanRequest,
+) -> Result<DcaPlan, String> {
+    let existing_plan = fetch_dca_plan(pool.inner(), &id).await?;
+    let updated_plan = DcaPlan {
+        id: existing_plan.id,
+        name: request.name.trim().to_string(),
+        amount_cents: request.amount_cents,
+        frequency: request.frequency,
+        day_of_week: request.day_of_week,
+        day_of_month: request.day_of_month,
+        provider: request.provider,
+        account_id: request.account_id,
+        timezone: request.timezone,
+        grace_days: request.grace_days.unwrap_or(DEFAULT_GRACE_DAYS),
+        start_date: request.start_date,
+        end_date: request.end_date,
+        created_at: existing_plan.created_at,
+    };
+    validate_plan(&updated_plan)?;
+
+    sqlx::query(
+        "UPDATE dca_plans SET name = ?, amount_cents = ?, frequency = ?, day_of_week = ?, day_of_month = ?, provider = ?, account_id = ?, timezone = ?, grace_days = ?, start_date = ?, end_date = ? WHERE id = ?"
+    )
+    .bind(&updated_plan.name)
+    .bind(updated_plan.amount_cents)
+    .bind(updated_plan.frequency.to_string())
+    .bind(updated_plan.day_of_week.map(|day| day as i64))
+    .bind(updated_plan.day_of_month.map(|day| day as i64))
+    .bind(&updated_plan.provider)
+    .bind(&updated_plan.account_id)
+    .bind(&updated_plan.timezone)
+    .bind(updated_plan.grace_days)
+    .bind(updated_plan.start_date)
+    .bind(updated_plan.end_date)
+    .bind(&updated_plan.id)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Updated DCA plan: {:?}", updated_plan);
+    Ok(updated_plan)
+}
+
+#[tauri::command]
+pub async fn delete_dca_plan(pool: State<'_, SqlitePool>, id: String) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM dca_plans WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("DCA plan not found".to_string());
+    }
+
+    println!("Deleted DCA plan with id: {}", id);
+    Ok(())
+}
+
+/// Compares actual buys with the occurrences a plan committed to.
+///
+/// Each occurrence claims the matching buys (same provider and account, when the plan sets
+/// them) made on its local date, or up to `grace_days` later but before the next occurrence.
+/// Buys on the date are on time, later ones late. Buys no occurrence claims are extras.
+#[tauri::command]
+pub async fn get_dca_plan_adherence(
+    pool: State<'_, SqlitePool>,
+    id: String,
+) -> Result<DcaPlanAdherence, String> {
+    let plan = fetch_dca_plan(pool.inner(), &id).await?;
+    let tz = parse_timezone(plan.timezone.as_deref())?;
+
+    let now = Utc::now();
+    let today = now.with_timezone(&tz).date_naive();
+    let first_day = plan.start_date.with_timezone(&tz).date_naive();
+    let last_day = plan
+        .end_date
+        .map(|end| end.with_timezone(&tz).date_naive().min(today))
+        .unwrap_or(today);
+
+    let dates = if first_day <= last_day {
+        scheduled_dates(&plan, first_day, last_day)
+    } else {
+        Vec::new()
+    };
+
+    let buy_rows = sqlx::query(&format!(
+        "SELECT id, timestamp, amount_sats, COALESCE(subtotal_cents, 0) + COALESCE(fee_cents, 0) as actual_cents
+        FROM ({}) AS events
+        WHERE transaction_type = 'buy'
+            AND (? IS NULL OR provider = ?)
+            AND (? IS NULL OR account_id = ?)
+            AND timestamp >= ? AND timestamp <= ?
+        ORDER BY timestamp ASC",
+        UNIFIED_EVENTS_SQL
+    ))
+    .bind(&plan.provider)
+    .bind(&plan.provider)
+    .bind(&plan.account_id)
+    .bind(&plan.account_id)
+    .bind(local_midnight_utc(&tz, first_day))
+    .bind(plan.end_date.map_or(now, |end| end.min(now)))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    // Last local date each occurrence may still be met on
+    let window_ends: Vec<NaiveDate> = dates
+        .iter()
+        .enumerate()
+        .map(|(i, date)| {
+            let grace_end = date
+                .checked_add_days(Days::new(plan.grace_days as u64))
+                .unwrap_or(NaiveDate::MAX);
+            match dates.get(i + 1) {
+                Some(next) => grace_end.min(*next - Days::new(1)),
+                None => grace_end,
+            }
+        })
+        .collect();
+
+    let mut occurrences: Vec<PlanOccurrence> = dates
+        .iter()
+        .map(|date| PlanOccurrence {
+            scheduled_date: *date,
+            status: "missed".to_string(),
+            days_late: None,
+            buy_ids: Vec::new(),
+            actual_cents: 0,
+        })
+        .collect();
+    let mut extra_buys = Vec::new();
+
+    for row in &buy_rows {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let buy_date = timestamp.with_timezone(&tz).date_naive();
+        let actual_cents: i64 = row.get("actual_cents");
+
+        let index = dates.partition_point(|date| *date <= buy_date);
+        let occurrence = index
+            .checked_sub(1)
+            .filter(|i| buy_date <= window_ends[*i])
+            .map(|i| &mut occurrences[i]);
+
+        match occurrence {
+            Some(occurrence) => {
+                if occurrence.buy_ids.is_empty() {
+                    let days_late = (buy_date - occurrence.scheduled_date).num_days();
+                    occurrence.status = if days_late == 0 { "on_time" } else { "late" }.to_string();
+                    occurrence.days_late = Some(days_late);
+                }
+                occurrence.buy_ids.push(row.get("id"));
+                occurrence.actual_cents += actual_cents;
+            }
+            None => extra_buys.push(PlanExtraBuy {
+                id: row.get("id"),
+                timestamp,
+                amount_sats: row.get("amount_sats"),
+                actual_cents,
+            }),
+        }
+    }
+
+    // Unmet occurrences still inside their grace window are not missed yet
+    for (occurrence, window_end) in occurrences.iter_mut().zip(&window_ends) {
+        if occurrence.buy_ids.is_empty() && *window_end >= today {
+            occurrence.status = "pending".to_string();
+        }
+    }
+
+    let count_status = |status: &str| {
+        occurrences
+            .iter()
+            .filter(|occurrence| occurrence.status == status)
+            .count() as i64
+    };
+    let on_time_count = count_status("on_time");
+    let late_count = count_status("late");
+    let missed_count = count_status("missed");
+    let expected_count = on_time_count + late_count + missed_count;
+
+    let percent_of_expected = |count: i64| {
+        if expected_count > 0 {
+            Some(count as f64 / expected_count as f64 * 100.0)
+        } else {
+            None
+        }
+    };
+
+    let adherence = DcaPlanAdherence {
+        expected_count,
+        on_time_count,
+        late_count,
+        missed_count,
+        extra_count: extra_buys.len() as i64,
+        adherence_percent: percent_of_expected(on_time_count + late_count),
+        on_time_percent: percent_of_expected(on_time_count),
+        expected_cents: expected_count * plan.amount_cents,
+        actual_cents: occurrences.iter().map(|o| o.actual_cents).sum::<i64>()
+            + extra_buys.iter().map(|b| b.actual_cents).sum::<i64>(),
+        occurrences,
+        extra_buys,
+        plan,
+    };
+
+    println!(
+        "Plan {} adherence: {} on time, {} late, {} missed, {} extra",
+        adherence.plan.name,
+        adherence.on_time_count,
+        adherence.late_count,
+        adherence.missed_count,
+        adherence.extra_count
+    );
+    Ok(adherence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn monthly_plan(day_of_month: Option<u32>) -> DcaPlan {
+        DcaPlan {
+            id: "plan".to_string(),
+            name: "Monthly".to_string(),
+            amount_cents: 10_000,
+            frequency: PlanFrequency::Monthly,
+            day_of_week: None,
+            day_of_month,
+            provider: None,
+            account_id: None,
+            timezone: None,
+            grace_days: DEFAULT_GRACE_DAYS,
+            start_date: Utc::now(),
+            end_date: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn monthly_dates_clamp_to_the_end_of_short_months() {
+        let dates = scheduled_dates(&monthly_plan(Some(31)), date(2024, 1, 1), date(2024, 5, 31));
+        assert_eq!(
+            dates,
+            vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31), date(2024, 4, 30), date(2024, 5, 31)]
+        );
+
+        let dates = scheduled_dates(&monthly_plan(Some(30)), date(2023, 2, 1), date(2023, 3, 31));
+        assert_eq!(dates, vec![date(2023, 2, 28), date(2023, 3, 30)]);
+    }
+
+    #[test]
+    fn monthly_dates_default_to_the_first_day() {
+        let dates = scheduled_dates(&monthly_plan(None), date(2024, 1, 31), date(2024, 3, 31));
+        assert_eq!(dates, vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)]);
+    }
+
+    #[test]
+    fn monthly_dates_stay_inside_the_range() {
+        let dates = scheduled_dates(&monthly_plan(Some(15)), date(2024, 1, 20), date(2024, 3, 10));
+        assert_eq!(dates, vec![date(2024, 2, 15)]);
+    }
+}
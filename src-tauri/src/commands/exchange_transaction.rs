@@ -1,6 +1,9 @@
+use crate::commands::pagination::{
+    bind_cursor, decode_cursor, encode_cursor, keyset_sql, validate_page_size,
+};
 use crate::models::exchange_transaction::{
-    CreateExchangeTransactionRequest, ExchangeTransaction, PaginatedBitcoinTransactions,
-    UpdateExchangeTransactionRequest,
+    CreateExchangeTransactionRequest, ExchangeTransaction, KeysetBitcoinTransactions,
+    PaginatedBitcoinTransactions, UpdateExchangeTransactionRequest,
 };
 use chrono::{DateTime, Utc};
 use sqlx::{Row, SqlitePool};
@@ -22,10 +25,11 @@ pub async fn create_exchange_transaction(
         timestamp: request.timestamp,
         created_at: Utc::now(),
         provider_id: request.provider_id.clone(),
+        account_id: request.account_id.clone(),
     };
 
     sqlx::query(
-        "INSERT INTO exchange_transactions (id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO exchange_transactions (id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id, account_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&transaction.id)
     .bind(transaction.r#type.to_string())
@@ -36,6 +40,7 @@ pub async fn create_exchange_transaction(
     .bind(transaction.timestamp)
     .bind(transaction.created_at)
     .bind(&transaction.provider_id)
+    .bind(&transaction.account_id)
     .execute(pool.inner())
     .await
     .map_err(|e| format!("Database error: {}", e))?;
@@ -58,7 +63,7 @@ pub async fn get_exchange_transactions(
         .map_err(|e| format!("Database error: {}", e))?;
 
     let rows = sqlx::query(
-        "SELECT id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id FROM exchange_transactions ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+        "SELECT id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id, account_id FROM exchange_transactions ORDER BY timestamp DESC LIMIT ? OFFSET ?"
     )
     .bind(page_size as i64)
     .bind(offset as i64)
@@ -81,6 +86,7 @@ pub async fn get_exchange_transactions(
             timestamp: row.get("timestamp"),
             created_at: row.get("created_at"),
             provider_id: row.get("provider_id"),
+            account_id: row.get("account_id"),
         };
         transactions.push(transaction);
     }
@@ -107,6 +113,83 @@ pub async fn get_exchange_transactions(
     Ok(result)
 }
 
+/// Cursor-based variant of `get_exchange_transactions`. Pass the previous `next_cursor` to
+/// continue; rows inserted meanwhile never shift or repeat the following pages.
+#[tauri::command]
+pub async fn get_exchange_transactions_after(
+    pool: State<'_, SqlitePool>,
+    cursor: Option<String>,
+    page_size: u32,
+    include_count: Option<bool>,
+) -> Result<KeysetBitcoinTransactions, String> {
+    validate_page_size(page_size)?;
+    let cursor = decode_cursor(cursor.as_deref())?;
+
+    let total_count: Option<i64> = if include_count.unwrap_or(false) {
+        Some(
+            sqlx::query_scalar("SELECT COUNT(*) FROM exchange_transactions")
+                .fetch_one(pool.inner())
+                .await
+                .map_err(|e| format!("Database error: {}", e))?,
+        )
+    } else {
+        None
+    };
+
+    // One extra row tells whether another page follows
+    let sql = format!(
+        "SELECT id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id, account_id
+        FROM exchange_transactions
+        WHERE {}
+        ORDER BY timestamp DESC, id DESC
+        LIMIT ?",
+        keyset_sql(&cursor, "timestamp", "id")
+    );
+    let mut rows = bind_cursor(sqlx::query(&sql), &cursor)
+        .bind(page_size as i64 + 1)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let has_more = rows.len() > page_size as usize;
+    rows.truncate(page_size as usize);
+    let next_cursor = if has_more { rows.last().map(encode_cursor) } else { None };
+
+    let mut transactions = Vec::new();
+    for row in rows {
+        let transaction = ExchangeTransaction {
+            id: row.get("id"),
+            r#type: row
+                .get::<String, _>("type")
+                .parse()
+                .map_err(|e| format!("Invalid transaction type: {}", e))?,
+            amount_sats: row.get("amount_sats"),
+            subtotal_cents: row.get("subtotal_cents"),
+            fee_cents: row.get("fee_cents"),
+            memo: row.get("memo"),
+            timestamp: row.get("timestamp"),
+            created_at: row.get("created_at"),
+            provider_id: row.get("provider_id"),
+            account_id: row.get("account_id"),
+        };
+        transactions.push(transaction);
+    }
+
+    println!(
+        "Retrieved {} bitcoin transactions after cursor {:?} (has_more: {})",
+        transactions.len(),
+        cursor,
+        has_more
+    );
+    Ok(KeysetBitcoinTransactions {
+        transactions,
+        page_size,
+        next_cursor,
+        has_more,
+        total_count,
+    })
+}
+
 #[tauri::command]
 pub async fn update_exchange_transaction(
     pool: State<'_, SqlitePool>,
@@ -114,7 +197,7 @@ pub async fn update_exchange_transaction(
     request: UpdateExchangeTransactionRequest,
 ) -> Result<ExchangeTransaction, String> {
     sqlx::query(
-        "UPDATE exchange_transactions SET type = ?, amount_sats = ?, subtotal_cents = ?, fee_cents = ?, memo = ?, timestamp = ?, provider_id = ? WHERE id = ?"
+        "UPDATE exchange_transactions SET type = ?, amount_sats = ?, subtotal_cents = ?, fee_cents = ?, memo = ?, timestamp = ?, provider_id = ?, account_id = CASE WHEN ? THEN NULL ELSE COALESCE(?, account_id) END WHERE id = ?"
     )
     .bind(request.r#type.to_string())
     .bind(request.amount_sats)
@@ -123,13 +206,15 @@ pub async fn update_exchange_transaction(
     .bind(&request.memo)
     .bind(request.timestamp)
     .bind(&request.provider_id)
+    .bind(request.clear_account.unwrap_or(false))
+    .bind(&request.account_id)
     .bind(&id)
     .execute(pool.inner())
     .await
     .map_err(|e| format!("Database error: {}", e))?;
 
     let row = sqlx::query(
-        "SELECT id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id FROM exchange_transactions WHERE id = ?"
+        "SELECT id, type, amount_sats, subtotal_cents, fee_cents, memo, timestamp, created_at, provider_id, account_id FROM exchange_transactions WHERE id = ?"
     )
     .bind(&id)
     .fetch_one(pool.inner())
@@ -149,6 +234,7 @@ pub async fn update_exchange_transaction(
         timestamp: row.get("timestamp"),
         created_at: row.get("created_at"),
         provider_id: row.get("provider_id"),
+        account_id: row.get("account_id"),
     };
 
     println!("Updated bitcoin transaction: {:?}", updated_transaction);
@@ -0,0 +1,204 @@
+use crate::commands::overview_tool::calculate_overview_metrics;
+use crate::commands::price_history::{load_daily_closes, price_at};
+use crate::commands::unified_events::UNIFIED_EVENTS_SQL;
+use crate::models::fee_analysis::{BuyFeeDetail, FeeAnalysis, FeeTrend};
+use crate::models::overview::OverviewFilter;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use tauri::State;
+
+#[derive(Default)]
+struct FeeTrendTotals {
+    buy_count: i64,
+    volume_cents: i64,
+    fees_cents: i64,
+    priced_volume_cents: i64,
+    weighted_spread: f64,
+}
+
+impl FeeTrendTotals {
+    fn add(&mut self, buy: &BuyFeeDetail) {
+        self.buy_count += 1;
+        self.volume_cents += buy.subtotal_cents;
+        self.fees_cents += buy.fee_cents;
+        if let Some(spread_percent) = buy.spread_percent {
+            self.priced_volume_cents += buy.subtotal_cents;
+            self.weighted_spread += spread_percent * buy.subtotal_cents as f64;
+        }
+    }
+
+    fn into_trend(self, group: String) -> FeeTrend {
+        let fee_rate_percent = if self.volume_cents > 0 {
+            Some(self.fees_cents as f64 / self.volume_cents as f64 * 100.0)
+        } else {
+            None
+        };
+        let avg_spread_percent = if self.priced_volume_cents > 0 {
+            Some(self.weighted_spread / self.priced_volume_cents as f64)
+        } else {
+            None
+        };
+
+        FeeTrend {
+            group,
+            buy_count: self.buy_count,
+            volume_cents: self.volume_cents,
+            fees_cents: self.fees_cents,
+            fee_rate_percent,
+            avg_spread_percent,
+            total_cost_percent: fee_rate_percent.zip(avg_spread_percent).map(|(fee, spread)| fee + spread),
+        }
+    }
+}
+
+/// Breaks down what buying costs: the explicit fee rate of every buy, the spread paid over the
+/// stored prices of its UTC day, monthly and per-provider trends of both, and on-chain fees.
+///
+/// Only daily prices are stored, so the spread against the close is an estimate; the spread
+/// above the day's high is what was certainly overpaid. On-chain fees paid in the period are
+/// compared with the stack at `end_date`.
+#[tauri::command]
+pub async fn get_fee_analysis(
+    pool: State<'_, SqlitePool>,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+) -> Result<FeeAnalysis, String> {
+    let closes = load_daily_closes(
+        pool.inner(),
+        start_date.map(|d| d.date_naive()),
+        end_date.map(|d| d.date_naive()),
+    )
+    .await?;
+
+    // Buys are compared with their own UTC day only, never with a fallback day
+    let buy_rows = sqlx::query(&format!(
+        "SELECT events.id, events.timestamp, events.provider, events.amount_sats, events.subtotal_cents,
+            COALESCE(events.fee_cents, 0) as fee_cents, prices.low_cents, prices.high_cents, prices.close_cents
+        FROM ({}) AS events
+        LEFT JOIN price_history prices ON prices.date = date(events.timestamp)
+        WHERE events.transaction_type = 'buy' AND events.subtotal_cents > 0 AND events.amount_sats > 0
+            AND (? IS NULL OR events.timestamp >= ?) AND (? IS NULL OR events.timestamp <= ?)
+        ORDER BY events.timestamp ASC",
+        UNIFIED_EVENTS_SQL
+    ))
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut buys = Vec::new();
+    for row in &buy_rows {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let amount_sats: i64 = row.get("amount_sats");
+        let subtotal_cents: i64 = row.get("subtotal_cents");
+        let fee_cents: i64 = row.get("fee_cents");
+        let day_low_cents: Option<i64> = row.get("low_cents");
+        let day_high_cents: Option<i64> = row.get("high_cents");
+        let market_price_cents: Option<i64> = row.get("close_cents");
+
+        let paid_price_cents = (subtotal_cents as f64 * 100_000_000.0 / amount_sats as f64).round() as i64;
+
+        buys.push(BuyFeeDetail {
+            id: row.get("id"),
+            timestamp,
+            provider: row.get("provider"),
+            amount_sats,
+            subtotal_cents,
+            fee_cents,
+            fee_rate_percent: fee_cents as f64 / subtotal_cents as f64 * 100.0,
+            paid_price_cents,
+            market_price_cents,
+            day_low_cents,
+            day_high_cents,
+            spread_percent: market_price_cents
+                .filter(|market| *market > 0)
+                .map(|market| (paid_price_cents - market) as f64 / market as f64 * 100.0),
+            min_spread_percent: day_high_cents
+                .filter(|high| *high > 0)
+                .map(|high| ((paid_price_cents - high) as f64 / high as f64 * 100.0).max(0.0)),
+        });
+    }
+
+    let mut monthly_totals: BTreeMap<String, FeeTrendTotals> = BTreeMap::new();
+    let mut provider_totals: BTreeMap<String, FeeTrendTotals> = BTreeMap::new();
+    for buy in &buys {
+        monthly_totals
+            .entry(buy.timestamp.format("%Y-%m").to_string())
+            .or_default()
+            .add(buy);
+        provider_totals.entry(buy.provider.clone()).or_default().add(buy);
+    }
+
+    let monthly: Vec<FeeTrend> = monthly_totals
+        .into_iter()
+        .map(|(month, totals)| totals.into_trend(month))
+        .collect();
+    let by_provider: Vec<FeeTrend> = provider_totals
+        .into_iter()
+        .map(|(provider, totals)| totals.into_trend(provider))
+        .collect();
+
+    let fee_rows = sqlx::query(
+        "SELECT timestamp, amount_sats FROM onchain_fees
+        WHERE (? IS NULL OR timestamp >= ?) AND (? IS NULL OR timestamp <= ?)"
+    )
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut total_onchain_fees_sats = 0;
+    let mut onchain_fees_fiat_cents = 0;
+    let mut unpriced_onchain_fee_count = 0;
+    for row in &fee_rows {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let amount_sats: i64 = row.get("amount_sats");
+
+        total_onchain_fees_sats += amount_sats;
+        match price_at(&closes, timestamp) {
+            Some(price_cents) => {
+                onchain_fees_fiat_cents +=
+                    (amount_sats as f64 * price_cents as f64 / 100_000_000.0).round() as i64
+            }
+            None => unpriced_onchain_fee_count += 1,
+        }
+    }
+
+    let stack = calculate_overview_metrics(
+        pool.inner(),
+        &OverviewFilter {
+            as_of: end_date,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let onchain_fees_percent_of_stack = if stack.current_sats > 0 {
+        Some(total_onchain_fees_sats as f64 / stack.current_sats as f64 * 100.0)
+    } else {
+        None
+    };
+
+    let analysis = FeeAnalysis {
+        buys,
+        monthly,
+        by_provider,
+        total_onchain_fees_sats,
+        onchain_fees_percent_of_stack,
+        onchain_fees_fiat_cents,
+        unpriced_onchain_fee_count,
+    };
+
+    println!(
+        "Analyzed fees of {} buys and {} on-chain fees",
+        analysis.buys.len(),
+        fee_rows.len()
+    );
+    Ok(analysis)
+}
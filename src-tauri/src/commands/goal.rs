@@ -0,0 +1,223 @@
+use crate::commands::overview_tool::net_sats_between;
+use crate::models::goal::{CreateGoalRequest, Goal, GoalProgress, UpdateGoalRequest};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+use uuid::Uuid;
+
+const DEFAULT_TRAILING_WEEKS: i64 = 12;
+
+fn goal_from_row(row: &SqliteRow) -> Goal {
+    Goal {
+        id: row.get("id"),
+        name: row.get("name"),
+        target_sats: row.get("target_sats"),
+        start_date: row.get("start_date"),
+        target_date: row.get("target_date"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn validate_goal(
+    name: &str,
+    target_sats: i64,
+    start_date: Option<DateTime<Utc>>,
+    target_date: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Goal name cannot be empty".to_string());
+    }
+    if target_sats <= 0 {
+        return Err("Target must be a positive number of sats".to_string());
+    }
+    if let (Some(start), Some(target)) = (start_date, target_date) {
+        if start >= target {
+            return Err("Start date must be before the target date".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_goal(
+    pool: State<'_, SqlitePool>,
+    request: CreateGoalRequest,
+) -> Result<Goal, String> {
+    validate_goal(&request.name, request.target_sats, request.start_date, request.target_date)?;
+
+    let goal = Goal {
+        id: Uuid::new_v4().to_string(),
+        name: request.name.trim().to_string(),
+        target_sats: request.target_sats,
+        start_date: request.start_date,
+        target_date: request.target_date,
+        created_at: Utc::now(),
+    };
+
+    sqlx::query(
+        "INSERT INTO goals (id, name, target_sats, start_date, target_date, created_at) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&goal.id)
+    .bind(&goal.name)
+    .bind(goal.target_sats)
+    .bind(goal.start_date)
+    .bind(goal.target_date)
+    .bind(goal.created_at)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Created goal: {:?}", goal);
+    Ok(goal)
+}
+
+#[tauri::command]
+pub async fn get_goals(pool: State<'_, SqlitePool>) -> Result<Vec<Goal>, String> {
+    let rows = sqlx::query(
+        "SELECT id, name, target_sats, start_date, target_date, created_at FROM goals ORDER BY created_at ASC"
+    )
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let goals: Vec<Goal> = rows.iter().map(goal_from_row).collect();
+
+    println!("Retrieved {} goals", goals.len());
+    Ok(goals)
+}
+
+#[tauri::command]
+pub async fn update_goal(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    request: UpdateGoalRequest,
+) -> Result<Goal, String> {
+    validate_goal(&request.name, request.target_sats, request.start_date, request.target_date)?;
+
+    let result = sqlx::query(
+        "UPDATE goals SET name = ?, target_sats = ?, start_date = ?, target_date = ? WHERE id = ?"
+    )
+    .bind(request.name.trim())
+    .bind(request.target_sats)
+    .bind(request.start_date)
+    .bind(request.target_date)
+    .bind(&id)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Goal not found".to_string());
+    }
+
+    let row = sqlx::query(
+        "SELECT id, name, target_sats, start_date, target_date, created_at FROM goals WHERE id = ?"
+    )
+    .bind(&id)
+    .fetch_one(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let updated_goal = goal_from_row(&row);
+
+    println!("Updated goal: {:?}", updated_goal);
+    Ok(updated_goal)
+}
+
+#[tauri::command]
+pub async fn delete_goal(pool: State<'_, SqlitePool>, id: String) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM goals WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Goal not found".to_string());
+    }
+
+    println!("Deleted goal with id: {}", id);
+    Ok(())
+}
+
+/// Progress on every goal, with the weekly rate needed to hit its target date and a projected
+/// completion date at the net stacking rate of the trailing `trailing_weeks` (default 12).
+#[tauri::command]
+pub async fn get_goal_progress(
+    pool: State<'_, SqlitePool>,
+    trailing_weeks: Option<i64>,
+) -> Result<Vec<GoalProgress>, String> {
+    let trailing_weeks = trailing_weeks.unwrap_or(DEFAULT_TRAILING_WEEKS);
+    if trailing_weeks < 1 {
+        return Err("Trailing window must be at least one week".to_string());
+    }
+
+    let now = Utc::now();
+    let trailing_start = now - Duration::weeks(trailing_weeks);
+    let trailing_sats = net_sats_between(pool.inner(), Some(trailing_start), Some(now)).await?;
+    let trailing_weekly_sats = trailing_sats / trailing_weeks;
+
+    let rows = sqlx::query(
+        "SELECT id, name, target_sats, start_date, target_date, created_at FROM goals ORDER BY created_at ASC"
+    )
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut progress = Vec::new();
+    for goal in rows.iter().map(goal_from_row) {
+        let current_sats = net_sats_between(pool.inner(), goal.start_date, Some(now)).await?;
+        let remaining_sats = (goal.target_sats - current_sats).max(0);
+        let is_complete = remaining_sats == 0;
+
+        let weeks_remaining = goal
+            .target_date
+            .map(|target| ((target - now).num_seconds() as f64 / (7.0 * 86_400.0)).max(0.0));
+        let required_weekly_sats = weeks_remaining.map(|weeks| {
+            if is_complete {
+                0
+            } else if weeks > 0.0 {
+                (remaining_sats as f64 / weeks).ceil() as i64
+            } else {
+                remaining_sats // past due, everything is needed now
+            }
+        });
+
+        let projected_completion_date = if is_complete {
+            Some(now)
+        } else if trailing_weekly_sats > 0 {
+            let weeks_needed = remaining_sats as f64 / trailing_weekly_sats as f64;
+            // Rates far too low to ever finish overflow the date range and stay None
+            Duration::try_seconds((weeks_needed * 7.0 * 86_400.0).ceil() as i64)
+                .and_then(|remaining| now.checked_add_signed(remaining))
+        } else {
+            None
+        };
+        let on_track = goal.target_date.map(|target| {
+            projected_completion_date
+                .map(|projected| projected <= target)
+                .unwrap_or(false)
+        });
+
+        progress.push(GoalProgress {
+            current_sats,
+            remaining_sats,
+            progress_percent: (current_sats as f64 / goal.target_sats as f64 * 100.0).clamp(0.0, 100.0),
+            is_complete,
+            weeks_remaining,
+            required_weekly_sats,
+            trailing_weekly_sats,
+            projected_completion_date,
+            on_track,
+            goal,
+        });
+    }
+
+    println!(
+        "Calculated progress for {} goals (trailing rate {} sats/week)",
+        progress.len(),
+        trailing_weekly_sats
+    );
+    Ok(progress)
+}
@@ -0,0 +1,476 @@
+use crate::models::lightning_payment::{
+    CreateLightningPaymentRequest, LightningImportResult, LightningPayment,
+    PaginatedLightningPayments, PaymentDirection, UpdateLightningPaymentRequest,
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+use uuid::Uuid;
+
+/// Converts a msat total to sats, rounding to the nearest sat. Only aggregates are converted so
+/// sub-sat routing fees still add up.
+pub(crate) fn msats_to_sats(msats: i64) -> i64 {
+    (msats as f64 / 1000.0).round() as i64
+}
+
+fn lightning_payment_from_row(row: &SqliteRow) -> Result<LightningPayment, String> {
+    Ok(LightningPayment {
+        id: row.get("id"),
+        direction: row
+            .get::<String, _>("direction")
+            .parse()
+            .map_err(|e| format!("Invalid payment direction: {}", e))?,
+        amount_msats: row.get("amount_msats"),
+        routing_fee_msats: row.get("routing_fee_msats"),
+        payment_hash: row.get("payment_hash"),
+        memo: row.get("memo"),
+        timestamp: row.get("timestamp"),
+        created_at: row.get("created_at"),
+        provider_id: row.get("provider_id"),
+        account_id: row.get("account_id"),
+    })
+}
+
+fn validate_amounts(amount_msats: i64, routing_fee_msats: i64) -> Result<(), String> {
+    if amount_msats < 0 || routing_fee_msats < 0 {
+        return Err("Lightning amounts cannot be negative, use the direction instead".to_string());
+    }
+    Ok(())
+}
+
+async fn insert_lightning_payment(
+    pool: &SqlitePool,
+    request: CreateLightningPaymentRequest,
+) -> Result<LightningPayment, String> {
+    validate_amounts(request.amount_msats, request.routing_fee_msats)?;
+
+    let payment = LightningPayment {
+        id: Uuid::new_v4().to_string(),
+        direction: request.direction,
+        amount_msats: request.amount_msats,
+        routing_fee_msats: request.routing_fee_msats,
+        payment_hash: request.payment_hash,
+        memo: request.memo,
+        timestamp: request.timestamp,
+        created_at: Utc::now(),
+        provider_id: request.provider_id,
+        account_id: request.account_id,
+    };
+
+    sqlx::query(
+        "INSERT INTO lightning_payments (id, direction, amount_msats, routing_fee_msats, payment_hash, memo, timestamp, created_at, provider_id, account_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&payment.id)
+    .bind(payment.direction.to_string())
+    .bind(payment.amount_msats)
+    .bind(payment.routing_fee_msats)
+    .bind(&payment.payment_hash)
+    .bind(&payment.memo)
+    .bind(payment.timestamp)
+    .bind(payment.created_at)
+    .bind(&payment.provider_id)
+    .bind(&payment.account_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(payment)
+}
+
+#[tauri::command]
+pub async fn create_lightning_payment(
+    pool: State<'_, SqlitePool>,
+    request: CreateLightningPaymentRequest,
+) -> Result<LightningPayment, String> {
+    let payment = insert_lightning_payment(pool.inner(), request).await?;
+
+    println!("Created lightning payment: {:?}", payment);
+    Ok(payment)
+}
+
+#[tauri::command]
+pub async fn get_lightning_payments(
+    pool: State<'_, SqlitePool>,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedLightningPayments, String> {
+    let offset = page * page_size;
+
+    let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lightning_payments")
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let rows = sqlx::query(
+        "SELECT id, direction, amount_msats, routing_fee_msats, payment_hash, memo, timestamp, created_at, provider_id, account_id FROM lightning_payments ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+    )
+    .bind(page_size as i64)
+    .bind(offset as i64)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut payments = Vec::new();
+    for row in rows {
+        payments.push(lightning_payment_from_row(&row)?);
+    }
+
+    let total_pages = ((total_count as f64) / (page_size as f64)).ceil() as u32;
+    let has_more = (page + 1) * page_size < total_count as u32;
+
+    let result = PaginatedLightningPayments {
+        payments,
+        total_count,
+        page,
+        page_size,
+        total_pages,
+        has_more,
+    };
+
+    println!(
+        "Retrieved {} lightning payments (page {} of {}, has_more: {})",
+        result.payments.len(),
+        page,
+        total_pages,
+        has_more
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn update_lightning_payment(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    request: UpdateLightningPaymentRequest,
+) -> Result<LightningPayment, String> {
+    validate_amounts(request.amount_msats, request.routing_fee_msats)?;
+
+    sqlx::query(
+        "UPDATE lightning_payments SET direction = ?, amount_msats = ?, routing_fee_msats = ?, payment_hash = ?, memo = ?, timestamp = ?, account_id = ? WHERE id = ?"
+    )
+    .bind(request.direction.to_string())
+    .bind(request.amount_msats)
+    .bind(request.routing_fee_msats)
+    .bind(&request.payment_hash)
+    .bind(&request.memo)
+    .bind(request.timestamp)
+    .bind(&request.account_id)
+    .bind(&id)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let row = sqlx::query(
+        "SELECT id, direction, amount_msats, routing_fee_msats, payment_hash, memo, timestamp, created_at, provider_id, account_id FROM lightning_payments WHERE id = ?"
+    )
+    .bind(&id)
+    .fetch_one(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let updated_payment = lightning_payment_from_row(&row)?;
+
+    println!("Updated lightning payment: {:?}", updated_payment);
+    Ok(updated_payment)
+}
+
+#[tauri::command]
+pub async fn delete_lightning_payment(
+    pool: State<'_, SqlitePool>,
+    id: String,
+) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM lightning_payments WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Lightning payment not found".to_string());
+    }
+
+    println!("Deleted lightning payment with id: {}", id);
+    Ok(())
+}
+
+// ============================================================================
+// LIGHTNING EXPORT IMPORT (Phoenix, Zeus/LND, Core Lightning)
+// ============================================================================
+
+#[derive(Debug)]
+enum LightningExportFormat {
+    Phoenix,
+    Lnd,
+    CoreLightning,
+}
+
+/// Reads a msat amount that may be a number, a numeric string, or a CLN style "1234msat" string.
+fn json_msats(value: Option<&Value>) -> Option<i64> {
+    match value? {
+        Value::Number(number) => number.as_i64(),
+        Value::String(s) => s.trim().trim_end_matches("msat").parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_unix_timestamp(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    let seconds = match value? {
+        Value::Number(number) => number.as_i64()?,
+        Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+    DateTime::from_timestamp(seconds, 0)
+}
+
+fn json_string(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// `lncli listpayments` output (also what Zeus exports for LND nodes). Only succeeded payments count.
+fn parse_lnd_payments(json: &Value) -> (Vec<CreateLightningPaymentRequest>, usize) {
+    let mut requests = Vec::new();
+    let mut skipped_count = 0;
+
+    for payment in json["payments"].as_array().into_iter().flatten() {
+        let succeeded = payment["status"].as_str().is_none_or(|s| s == "SUCCEEDED");
+        let amount_msats = json_msats(payment.get("value_msat"))
+            .or_else(|| json_msats(payment.get("value_sat")).map(|sats| sats * 1000));
+        let timestamp = json_unix_timestamp(payment.get("creation_date")).or_else(|| {
+            json_msats(payment.get("creation_time_ns"))
+                .map(DateTime::from_timestamp_nanos)
+        });
+
+        let (Some(amount_msats), Some(timestamp), true) = (amount_msats, timestamp, succeeded) else {
+            skipped_count += 1;
+            continue;
+        };
+
+        let routing_fee_msats = json_msats(payment.get("fee_msat"))
+            .or_else(|| json_msats(payment.get("fee_sat")).map(|sats| sats * 1000))
+            .unwrap_or(0);
+        let payment_hash = json_string(payment.get("payment_hash"));
+
+        requests.push(CreateLightningPaymentRequest {
+            direction: PaymentDirection::Outgoing,
+            amount_msats,
+            routing_fee_msats,
+            provider_id: payment_hash.as_ref().map(|hash| format!("lnd_{}", hash)),
+            payment_hash,
+            memo: Some("LND".to_string()),
+            timestamp,
+            account_id: None,
+        });
+    }
+
+    (requests, skipped_count)
+}
+
+/// Core Lightning `listpays` output. The routing fee is the difference between sent and delivered.
+fn parse_cln_pays(json: &Value) -> (Vec<CreateLightningPaymentRequest>, usize) {
+    let mut requests = Vec::new();
+    let mut skipped_count = 0;
+
+    for pay in json["pays"].as_array().into_iter().flatten() {
+        let complete = pay["status"].as_str() == Some("complete");
+        let amount_msats = json_msats(pay.get("amount_msat"));
+        let timestamp = json_unix_timestamp(pay.get("completed_at"))
+            .or_else(|| json_unix_timestamp(pay.get("created_at")));
+
+        let (Some(amount_msats), Some(timestamp), true) = (amount_msats, timestamp, complete) else {
+            skipped_count += 1;
+            continue;
+        };
+
+        let amount_sent_msats = json_msats(pay.get("amount_sent_msat")).unwrap_or(amount_msats);
+        let payment_hash = json_string(pay.get("payment_hash"));
+        let memo = json_string(pay.get("description"))
+            .or_else(|| json_string(pay.get("label")))
+            .map(|description| format!("Core Lightning: {}", description))
+            .unwrap_or_else(|| "Core Lightning".to_string());
+
+        requests.push(CreateLightningPaymentRequest {
+            direction: PaymentDirection::Outgoing,
+            amount_msats,
+            routing_fee_msats: (amount_sent_msats - amount_msats).max(0),
+            provider_id: payment_hash.as_ref().map(|hash| format!("cln_{}", hash)),
+            payment_hash,
+            memo: Some(memo),
+            timestamp,
+            account_id: None,
+        });
+    }
+
+    (requests, skipped_count)
+}
+
+/// Phoenix CSV export. `amount_msat` is the signed balance change including fees, so the
+/// payment amount is recovered by removing the fee from it.
+fn parse_phoenix_csv(content: &str) -> Result<(Vec<CreateLightningPaymentRequest>, usize), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+
+    let date_column = column("date").ok_or("Phoenix export is missing the date column")?;
+    let amount_column =
+        column("amount_msat").ok_or("Phoenix export is missing the amount_msat column")?;
+    let fees_column = column("fees_msat");
+    let service_fee_column = column("service_fee_msat");
+    let mining_fee_column = column("mining_fee_sat");
+    let id_column = column("id");
+    let type_column = column("type");
+    let hash_column = column("payment_hash");
+    let description_column = column("description");
+
+    let field = |record: &csv::StringRecord, index: Option<usize>| -> Option<String> {
+        index
+            .and_then(|i| record.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let number = |value: Option<String>| -> Result<i64, String> {
+        value
+            .map(|v| v.parse::<i64>().map_err(|_| format!("Invalid amount '{}'", v)))
+            .transpose()
+            .map(|v| v.unwrap_or(0))
+    };
+
+    let mut requests = Vec::new();
+    let mut skipped_count = 0;
+
+    for result in reader.records() {
+        let record = result.map_err(|e| format!("Failed to parse CSV record: {}", e))?;
+
+        // Swaps and channel operations are on-chain, only lightning payments are imported here
+        if let Some(kind) = field(&record, type_column) {
+            if !kind.starts_with("lightning") {
+                skipped_count += 1;
+                continue;
+            }
+        }
+
+        let date = field(&record, Some(date_column)).unwrap_or_default();
+        let timestamp = DateTime::parse_from_rfc3339(&date)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| format!("Failed to parse Phoenix timestamp '{}': {}", date, e))?;
+
+        let balance_change_msats = number(field(&record, Some(amount_column)))?;
+        let routing_fee_msats = (number(field(&record, fees_column))?
+            + number(field(&record, service_fee_column))?
+            + number(field(&record, mining_fee_column))? * 1000)
+            .abs();
+
+        let (direction, amount_msats) = if balance_change_msats < 0 {
+            (
+                PaymentDirection::Outgoing,
+                (balance_change_msats.abs() - routing_fee_msats).max(0),
+            )
+        } else {
+            (
+                PaymentDirection::Incoming,
+                balance_change_msats + routing_fee_msats,
+            )
+        };
+
+        let payment_hash = field(&record, hash_column);
+        let provider_id = field(&record, id_column)
+            .or_else(|| payment_hash.clone())
+            .unwrap_or_else(|| format!("{}_{}", timestamp.timestamp_millis(), balance_change_msats));
+        let memo = field(&record, description_column)
+            .map(|description| format!("Phoenix: {}", description))
+            .unwrap_or_else(|| "Phoenix".to_string());
+
+        requests.push(CreateLightningPaymentRequest {
+            direction,
+            amount_msats,
+            routing_fee_msats,
+            payment_hash,
+            memo: Some(memo),
+            timestamp,
+            provider_id: Some(format!("phoenix_{}", provider_id)),
+            account_id: None,
+        });
+    }
+
+    Ok((requests, skipped_count))
+}
+
+fn parse_lightning_export(
+    content: &str,
+) -> Result<(LightningExportFormat, Vec<CreateLightningPaymentRequest>, usize), String> {
+    if let Ok(json) = serde_json::from_str::<Value>(content) {
+        if json.get("payments").is_some() {
+            let (requests, skipped_count) = parse_lnd_payments(&json);
+            return Ok((LightningExportFormat::Lnd, requests, skipped_count));
+        }
+        if json.get("pays").is_some() {
+            let (requests, skipped_count) = parse_cln_pays(&json);
+            return Ok((LightningExportFormat::CoreLightning, requests, skipped_count));
+        }
+        return Err("Unrecognized JSON export. Expected `lncli listpayments` or `lightning-cli listpays` output".to_string());
+    }
+
+    let first_line = content.lines().next().unwrap_or("");
+    if first_line.contains("date") && first_line.contains("amount_msat") {
+        let (requests, skipped_count) = parse_phoenix_csv(content)?;
+        return Ok((LightningExportFormat::Phoenix, requests, skipped_count));
+    }
+
+    Err("Unrecognized lightning export. Expected Phoenix CSV, LND or Core Lightning JSON".to_string())
+}
+
+#[tauri::command]
+pub async fn import_lightning_payments(
+    pool: State<'_, SqlitePool>,
+    file_path: String,
+    account_id: Option<String>,
+) -> Result<LightningImportResult, String> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read file '{}': {}", file_path, e))?;
+
+    let (format, requests, skipped_count) = parse_lightning_export(&content)?;
+    println!("Parsed {} payments from {:?} export", requests.len(), format);
+
+    let mut imported_count = 0;
+    let mut duplicate_count = 0;
+
+    for mut request in requests {
+        if let Some(provider_id) = &request.provider_id {
+            let exists: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM lightning_payments WHERE provider_id = ?")
+                    .bind(provider_id)
+                    .fetch_one(pool.inner())
+                    .await
+                    .map_err(|e| format!("Database error checking for existing payment: {}", e))?;
+
+            if exists > 0 {
+                duplicate_count += 1;
+                continue;
+            }
+        }
+
+        request.account_id = account_id.clone();
+        insert_lightning_payment(pool.inner(), request).await?;
+        imported_count += 1;
+    }
+
+    let result = LightningImportResult {
+        format: format!("{:?}", format),
+        imported_count,
+        duplicate_count,
+        skipped_count,
+    };
+
+    println!("Lightning import completed: {:?}", result);
+    Ok(result)
+}
@@ -1,4 +1,6 @@
 use crate::commands::exchange_transaction::create_exchange_transaction;
+use crate::commands::tag::{assign_tag, find_or_create_tag};
+use crate::commands::settings::load_settings;
 use crate::database::get_database_path;
 use crate::models::exchange_transaction::{
     CreateExchangeTransactionRequest, ExchangeTransaction, TransactionType,
@@ -220,7 +222,8 @@ pub async fn encrypt_database(app_handle: AppHandle, password: String) -> Result
         .pragma_update(None, "key", &password)
         .map_err(|e| format!("Failed to set encryption key: {}", e))?;
 
-    let mut stmt = backup_conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '_sqlx_%'")
+    // The search index and its shadow tables are rebuilt by triggers as the event tables are copied
+    let mut stmt = backup_conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '_sqlx_%' AND name NOT LIKE 'event_search%'")
         .map_err(|e| format!("Failed to prepare table query: {}", e))?;
 
     let table_names: Result<Vec<String>, _> = stmt
@@ -484,6 +487,7 @@ pub async fn import_sat_tracker_v1_data(pool: State<'_, SqlitePool>) -> Result<S
                     memo,
                     timestamp,
                     provider_id: Some(provider_id),
+                    account_id: None,
                 };
 
                 match create_exchange_transaction(pool.clone(), request).await {
@@ -555,6 +559,7 @@ pub async fn import_sat_tracker_v1_data(pool: State<'_, SqlitePool>) -> Result<S
                     memo,
                     timestamp,
                     provider_id: Some(provider_id),
+                    account_id: None,
                 };
 
                 match create_exchange_transaction(pool.clone(), request).await {
@@ -769,6 +774,9 @@ async fn process_coinbase_csv(
     let csv_content = lines[headers_line..].join("\n");
     let mut reader = csv::Reader::from_reader(csv_content.as_bytes());
     let mut events = Vec::new();
+    let grouping_window_seconds = load_settings(pool.inner())
+        .await?
+        .coinbase_grouping_window_seconds;
 
     let mut btc_records = Vec::new();
 
@@ -820,7 +828,7 @@ async fn process_coinbase_csv(
 
                 let time_diff = (current_timestamp - last_timestamp).num_seconds().abs();
 
-                if time_diff <= 5 {
+                if time_diff <= grouping_window_seconds {
                     current_group.push(record);
                 } else {
                     grouped_records.push(current_group);
@@ -888,6 +896,7 @@ async fn process_coinbase_csv(
                 memo: Some(memo),
                 timestamp,
                 provider_id: Some(provider_id),
+                account_id: None,
             };
 
             let transaction = create_exchange_transaction(pool.clone(), request).await?;
@@ -930,6 +939,9 @@ pub async fn create_undocumented_lumpsum_transactions(
         format!("DCA {}", id)
     });
 
+    // The label doubles as a real tag so the generated batch can be filtered and renamed later
+    let batch_tag = find_or_create_tag(pool.inner(), &final_memo).await?;
+
     let interval_days = match frequency.as_str() {
         "daily" => 1,
         "weekly" => 7,
@@ -972,10 +984,14 @@ pub async fn create_undocumented_lumpsum_transactions(
             memo: Some(final_memo.clone()),
             timestamp: current_date,
             provider_id: None,
+            account_id: None,
         };
 
         match create_exchange_transaction(pool.clone(), request).await {
-            Ok(transaction) => created_transactions.push(transaction),
+            Ok(transaction) => {
+                assign_tag(pool.inner(), &batch_tag.id, "exchange_transaction", &transaction.id).await?;
+                created_transactions.push(transaction)
+            }
             Err(e) => return Err(format!("Failed to create transaction {}: {}", i + 1, e)),
         }
 
@@ -1110,6 +1126,7 @@ async fn process_river_buy_transaction(
         memo: Some("River".to_string()),
         timestamp: *timestamp,
         provider_id: Some(provider_id),
+        account_id: None,
     };
 
     let transaction = create_exchange_transaction(pool, request).await?;
@@ -1160,6 +1177,7 @@ async fn process_river_sell_transaction(
         memo: Some("River".to_string()),
         timestamp: *timestamp,
         provider_id: Some(provider_id),
+        account_id: None,
     };
 
     let transaction = create_exchange_transaction(pool, request).await?;
@@ -1,7 +1,26 @@
 pub mod exchange_transaction;
 pub mod api;
+pub mod price_provider;
 pub mod activity_tool;
 pub mod onchain_fee;
 pub mod unified_events;
 pub mod overview_tool;
 pub mod menu_tools;
+pub mod account;
+pub mod watch_only_wallet;
+pub mod reconciliation;
+pub mod lightning_payment;
+pub mod spending_event;
+pub mod cost_basis;
+pub mod tag;
+pub mod pagination;
+pub mod time_buckets;
+pub mod price_history;
+pub mod fee_analysis;
+pub mod performance;
+pub mod strategy_simulation;
+pub mod timing_score;
+pub mod goal;
+pub mod achievement;
+pub mod dca_plan;
+pub mod settings;
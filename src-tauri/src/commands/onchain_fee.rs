@@ -1,5 +1,9 @@
+use crate::commands::pagination::{
+    bind_cursor, decode_cursor, encode_cursor, keyset_sql, validate_page_size,
+};
 use crate::models::onchain_fee::{
     OnchainFee, CreateOnchainFeeRequest, UpdateOnchainFeeRequest, PaginatedOnchainFees,
+    KeysetOnchainFees,
 };
 use chrono::Utc;
 use sqlx::{Row, SqlitePool};
@@ -18,10 +22,11 @@ pub async fn create_onchain_fee(
         timestamp: request.timestamp,
         created_at: Utc::now(),
         tx_hash: request.tx_hash.clone(),
+        account_id: request.account_id.clone(),
     };
 
     sqlx::query(
-        "INSERT INTO onchain_fees (id, amount_sats, memo, timestamp, created_at, tx_hash) VALUES (?, ?, ?, ?, ?, ?)"
+        "INSERT INTO onchain_fees (id, amount_sats, memo, timestamp, created_at, tx_hash, account_id) VALUES (?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&fee.id)
     .bind(fee.amount_sats)
@@ -29,6 +34,7 @@ pub async fn create_onchain_fee(
     .bind(fee.timestamp)
     .bind(fee.created_at)
     .bind(&fee.tx_hash)
+    .bind(&fee.account_id)
     .execute(pool.inner())
     .await
     .map_err(|e| format!("Database error: {}", e))?;
@@ -51,7 +57,7 @@ pub async fn get_onchain_fees(
         .map_err(|e| format!("Database error: {}", e))?;
 
     let rows = sqlx::query(
-        "SELECT id, amount_sats, memo, timestamp, created_at, tx_hash FROM onchain_fees ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+        "SELECT id, amount_sats, memo, timestamp, created_at, tx_hash, account_id FROM onchain_fees ORDER BY timestamp DESC LIMIT ? OFFSET ?"
     )
     .bind(page_size as i64)
     .bind(offset as i64)
@@ -68,6 +74,7 @@ pub async fn get_onchain_fees(
             timestamp: row.get("timestamp"),
             created_at: row.get("created_at"),
             tx_hash: row.get("tx_hash"),
+            account_id: row.get("account_id"),
         };
         fees.push(fee);
     }
@@ -94,6 +101,76 @@ pub async fn get_onchain_fees(
     Ok(result)
 }
 
+/// Cursor-based variant of `get_onchain_fees`, see `get_exchange_transactions_after`.
+#[tauri::command]
+pub async fn get_onchain_fees_after(
+    pool: State<'_, SqlitePool>,
+    cursor: Option<String>,
+    page_size: u32,
+    include_count: Option<bool>,
+) -> Result<KeysetOnchainFees, String> {
+    validate_page_size(page_size)?;
+    let cursor = decode_cursor(cursor.as_deref())?;
+
+    let total_count: Option<i64> = if include_count.unwrap_or(false) {
+        Some(
+            sqlx::query_scalar("SELECT COUNT(*) FROM onchain_fees")
+                .fetch_one(pool.inner())
+                .await
+                .map_err(|e| format!("Database error: {}", e))?,
+        )
+    } else {
+        None
+    };
+
+    // One extra row tells whether another page follows
+    let sql = format!(
+        "SELECT id, amount_sats, memo, timestamp, created_at, tx_hash, account_id
+        FROM onchain_fees
+        WHERE {}
+        ORDER BY timestamp DESC, id DESC
+        LIMIT ?",
+        keyset_sql(&cursor, "timestamp", "id")
+    );
+    let mut rows = bind_cursor(sqlx::query(&sql), &cursor)
+        .bind(page_size as i64 + 1)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let has_more = rows.len() > page_size as usize;
+    rows.truncate(page_size as usize);
+    let next_cursor = if has_more { rows.last().map(encode_cursor) } else { None };
+
+    let mut fees = Vec::new();
+    for row in rows {
+        let fee = OnchainFee {
+            id: row.get("id"),
+            amount_sats: row.get("amount_sats"),
+            memo: row.get("memo"),
+            timestamp: row.get("timestamp"),
+            created_at: row.get("created_at"),
+            tx_hash: row.get("tx_hash"),
+            account_id: row.get("account_id"),
+        };
+        fees.push(fee);
+    }
+
+    println!(
+        "Retrieved {} onchain fees after cursor {:?} (has_more: {})",
+        fees.len(),
+        cursor,
+        has_more
+    );
+    Ok(KeysetOnchainFees {
+        fees,
+        page_size,
+        next_cursor,
+        has_more,
+        total_count,
+    })
+}
+
 #[tauri::command]
 pub async fn update_onchain_fee(
     pool: State<'_, SqlitePool>,
@@ -101,19 +178,21 @@ pub async fn update_onchain_fee(
     request: UpdateOnchainFeeRequest,
 ) -> Result<OnchainFee, String> {
     sqlx::query(
-        "UPDATE onchain_fees SET amount_sats = ?, memo = ?, timestamp = ?, tx_hash = ? WHERE id = ?"
+        "UPDATE onchain_fees SET amount_sats = ?, memo = ?, timestamp = ?, tx_hash = ?, account_id = CASE WHEN ? THEN NULL ELSE COALESCE(?, account_id) END WHERE id = ?"
     )
     .bind(request.amount_sats)
     .bind(&request.memo)
     .bind(request.timestamp)
     .bind(&request.tx_hash)
+    .bind(request.clear_account.unwrap_or(false))
+    .bind(&request.account_id)
     .bind(&id)
     .execute(pool.inner())
     .await
     .map_err(|e| format!("Database error: {}", e))?;
 
     let row = sqlx::query(
-        "SELECT id, amount_sats, memo, timestamp, created_at, tx_hash FROM onchain_fees WHERE id = ?"
+        "SELECT id, amount_sats, memo, timestamp, created_at, tx_hash, account_id FROM onchain_fees WHERE id = ?"
     )
     .bind(&id)
     .fetch_one(pool.inner())
@@ -127,6 +206,7 @@ pub async fn update_onchain_fee(
         timestamp: row.get("timestamp"),
         created_at: row.get("created_at"),
         tx_hash: row.get("tx_hash"),
+        account_id: row.get("account_id"),
     };
 
     println!("Updated onchain fee: {:?}", updated_fee);
@@ -1,13 +1,93 @@
-use crate::models::overview::OverviewMetrics;
+use crate::commands::cost_basis::calculate_realized_gains;
+use crate::commands::lightning_payment::msats_to_sats;
+use crate::commands::tag::{tag_filter_json, tag_filter_sql};
+use crate::commands::unified_events::UNIFIED_EVENTS_SQL;
+use crate::models::overview::{OverviewBreakdown, OverviewFilter, OverviewMetrics};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments};
 use sqlx::{Row, SqlitePool};
 use tauri::State;
 
 #[tauri::command]
 pub async fn get_overview_metrics(
     pool: State<'_, SqlitePool>,
+    filter: Option<OverviewFilter>,
 ) -> Result<OverviewMetrics, String> {
+    let overview_metrics =
+        calculate_overview_metrics(pool.inner(), &filter.unwrap_or_default()).await?;
+
+    println!("Calculated overview metrics: {:?}", overview_metrics);
+    Ok(overview_metrics)
+}
+
+/// Raw sums of every event table over one period.
+#[derive(Debug, Default)]
+struct PeriodTotals {
+    bought_sats: i64,
+    sold_sats: i64,
+    invested_cents: i64,
+    extracted_cents: i64,
+    buy_count: i64,
+    sell_count: i64,
+    onchain_fees_sats: i64,
+    net_adjustment_sats: i64,
+    lightning_sent_msats: i64,
+    lightning_received_msats: i64,
+    lightning_routing_fees_msats: i64,
+    spent_on_goods_sats: i64,
+    spending_fiat_cents: i64,
+}
+
+impl PeriodTotals {
+    fn lightning_spent_sats(&self) -> i64 {
+        msats_to_sats(self.lightning_sent_msats + self.lightning_routing_fees_msats)
+    }
+
+    fn lightning_received_sats(&self) -> i64 {
+        msats_to_sats(self.lightning_received_msats)
+    }
+
+    /// Change in holdings over the period.
+    fn net_sats(&self) -> i64 {
+        self.bought_sats - self.sold_sats - self.onchain_fees_sats - self.spent_on_goods_sats
+            + self.net_adjustment_sats
+            + self.lightning_received_sats()
+            - self.lightning_spent_sats()
+    }
+}
+
+/// Period and tag condition for `table`, bound by `bind_event_filter`.
+fn event_filter_sql(table: &str, record_type: &str) -> String {
+    format!(
+        "(? IS NULL OR {table}.timestamp >= ?) AND (? IS NULL OR {table}.timestamp <= ?) AND {}",
+        tag_filter_sql(table, &format!("'{}'", record_type))
+    )
+}
+
+fn bind_event_filter<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    tag_filter: &'q Option<String>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(start)
+        .bind(start)
+        .bind(end)
+        .bind(end)
+        .bind(tag_filter)
+        .bind(tag_filter)
+}
+
+async fn calculate_period_totals(
+    pool: &SqlitePool,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    tag_filter: &Option<String>,
+) -> Result<PeriodTotals, String> {
     // Query exchange transactions (no more fee type)
-    let exchange_row = sqlx::query(
+    let exchange_sql = format!(
         r#"
         SELECT 
             COALESCE(SUM(CASE WHEN type = 'buy' THEN amount_sats ELSE 0 END), 0) as total_bought_sats,
@@ -15,69 +95,276 @@ pub async fn get_overview_metrics(
             COALESCE(SUM(CASE WHEN type = 'buy' AND subtotal_cents IS NOT NULL THEN subtotal_cents ELSE 0 END), 0) as total_invested_cents,
             COALESCE(SUM(CASE WHEN type = 'sell' AND subtotal_cents IS NOT NULL THEN subtotal_cents ELSE 0 END), 0) as total_extracted_cents,
             COUNT(CASE WHEN type = 'buy' AND subtotal_cents IS NOT NULL THEN 1 END) as buy_count,
-            COUNT(CASE WHEN type = 'sell' AND subtotal_cents IS NOT NULL THEN 1 END) as sell_count,
-            COALESCE(SUM(CASE WHEN type = 'buy' AND timestamp >= datetime('now', '-7 days') THEN amount_sats ELSE 0 END), 0) as sats_stacked_7d,
-            COALESCE(SUM(CASE WHEN type = 'buy' AND subtotal_cents IS NOT NULL AND timestamp >= datetime('now', '-7 days') THEN subtotal_cents ELSE 0 END), 0) as usd_invested_7d_cents,
-            COALESCE(SUM(CASE WHEN type = 'buy' AND timestamp >= datetime('now', '-31 days') THEN amount_sats ELSE 0 END), 0) as sats_stacked_31d,
-            COALESCE(SUM(CASE WHEN type = 'buy' AND subtotal_cents IS NOT NULL AND timestamp >= datetime('now', '-31 days') THEN subtotal_cents ELSE 0 END), 0) as usd_invested_31d_cents
+            COUNT(CASE WHEN type = 'sell' AND subtotal_cents IS NOT NULL THEN 1 END) as sell_count
         FROM exchange_transactions
-        "#
-    )
-    .fetch_one(pool.inner())
-    .await
-    .map_err(|e| format!("Database error: {}", e))?;
+        WHERE {}
+        "#,
+        event_filter_sql("exchange_transactions", "exchange_transaction")
+    );
+    let exchange_row = bind_event_filter(sqlx::query(&exchange_sql), start, end, tag_filter)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
 
     // Query onchain fees
-    let fees_row = sqlx::query(
-        "SELECT COALESCE(SUM(amount_sats), 0) as total_onchain_fees_paid FROM onchain_fees"
+    let fees_sql = format!(
+        "SELECT COALESCE(SUM(amount_sats), 0) as total_onchain_fees_paid FROM onchain_fees WHERE {}",
+        event_filter_sql("onchain_fees", "onchain_fee")
+    );
+    let fees_row = bind_event_filter(sqlx::query(&fees_sql), start, end, tag_filter)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    // Query reconciliation adjustments
+    let adjustments_sql = format!(
+        "SELECT COALESCE(SUM(amount_sats), 0) as net_adjustment_sats FROM balance_adjustments WHERE {}",
+        event_filter_sql("balance_adjustments", "balance_adjustment")
+    );
+    let adjustments_row = bind_event_filter(sqlx::query(&adjustments_sql), start, end, tag_filter)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    // Query lightning payments (kept in msats until the final sats conversion)
+    let lightning_sql = format!(
+        r#"
+        SELECT
+            COALESCE(SUM(CASE WHEN direction = 'outgoing' THEN amount_msats ELSE 0 END), 0) as total_sent_msats,
+            COALESCE(SUM(CASE WHEN direction = 'incoming' THEN amount_msats ELSE 0 END), 0) as total_received_msats,
+            COALESCE(SUM(routing_fee_msats), 0) as total_routing_fees_msats
+        FROM lightning_payments
+        WHERE {}
+        "#,
+        event_filter_sql("lightning_payments", "lightning_payment")
+    );
+    let lightning_row = bind_event_filter(sqlx::query(&lightning_sql), start, end, tag_filter)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    // Query spending on goods and services
+    let spending_sql = format!(
+        "SELECT COALESCE(SUM(amount_sats), 0) as total_spent_sats, COALESCE(SUM(fiat_value_cents), 0) as total_spending_fiat_cents FROM spending_events WHERE {}",
+        event_filter_sql("spending_events", "spending")
+    );
+    let spending_row = bind_event_filter(sqlx::query(&spending_sql), start, end, tag_filter)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(PeriodTotals {
+        bought_sats: exchange_row.get("total_bought_sats"),
+        sold_sats: exchange_row.get("total_sold_sats"),
+        invested_cents: exchange_row.get("total_invested_cents"),
+        extracted_cents: exchange_row.get("total_extracted_cents"),
+        buy_count: exchange_row.get("buy_count"),
+        sell_count: exchange_row.get("sell_count"),
+        onchain_fees_sats: fees_row.get("total_onchain_fees_paid"),
+        net_adjustment_sats: adjustments_row.get("net_adjustment_sats"),
+        lightning_sent_msats: lightning_row.get("total_sent_msats"),
+        lightning_received_msats: lightning_row.get("total_received_msats"),
+        lightning_routing_fees_msats: lightning_row.get("total_routing_fees_msats"),
+        spent_on_goods_sats: spending_row.get("total_spent_sats"),
+        spending_fiat_cents: spending_row.get("total_spending_fiat_cents"),
+    })
+}
+
+/// Change in holdings between two instants, across every event type.
+pub(crate) async fn net_sats_between(
+    pool: &SqlitePool,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<i64, String> {
+    Ok(calculate_period_totals(pool, start, end, &None).await?.net_sats())
+}
+
+/// Overview metrics for the events selected by `filter`.
+///
+/// Flow totals (stacked, invested, spent, fees, gains) cover `start_date..=as_of`, while
+/// `current_sats` is always the full holdings at `as_of`. The 7 and 31 day windows end at
+/// `as_of`, which defaults to now.
+pub(crate) async fn calculate_overview_metrics(
+    pool: &SqlitePool,
+    filter: &OverviewFilter,
+) -> Result<OverviewMetrics, String> {
+    if let (Some(start_date), Some(as_of)) = (filter.start_date, filter.as_of) {
+        if start_date > as_of {
+            return Err("Start date must be before the as of date".to_string());
+        }
+    }
+
+    let tag_filter = tag_filter_json(filter.tag_ids.as_deref());
+    let window_end = filter.as_of.unwrap_or_else(Utc::now);
+
+    let totals = calculate_period_totals(pool, filter.start_date, filter.as_of, &tag_filter).await?;
+
+    // Holdings carried into the period
+    let opening_sats = match filter.start_date {
+        Some(start_date) => {
+            calculate_period_totals(pool, None, Some(start_date - Duration::microseconds(1)), &tag_filter)
+                .await?
+                .net_sats()
+        }
+        None => 0,
+    };
+
+    // Rolling windows ending at the as of date
+    let windows_sql = format!(
+        r#"
+        SELECT
+            COALESCE(SUM(CASE WHEN timestamp >= ? THEN amount_sats ELSE 0 END), 0) as sats_stacked_7d,
+            COALESCE(SUM(CASE WHEN subtotal_cents IS NOT NULL AND timestamp >= ? THEN subtotal_cents ELSE 0 END), 0) as usd_invested_7d_cents,
+            COALESCE(SUM(amount_sats), 0) as sats_stacked_31d,
+            COALESCE(SUM(CASE WHEN subtotal_cents IS NOT NULL THEN subtotal_cents ELSE 0 END), 0) as usd_invested_31d_cents
+        FROM exchange_transactions
+        WHERE type = 'buy' AND {}
+        "#,
+        event_filter_sql("exchange_transactions", "exchange_transaction")
+    );
+    let window_7d_start = window_end - Duration::days(7);
+    let windows_query = sqlx::query(&windows_sql)
+        .bind(window_7d_start)
+        .bind(window_7d_start);
+    let windows_row = bind_event_filter(
+        windows_query,
+        Some(window_end - Duration::days(31)),
+        Some(window_end),
+        &tag_filter,
     )
-    .fetch_one(pool.inner())
+    .fetch_one(pool)
     .await
     .map_err(|e| format!("Database error: {}", e))?;
 
-    let total_bought_sats: i64 = exchange_row.get("total_bought_sats");
-    let total_sold_sats: i64 = exchange_row.get("total_sold_sats");
-    let total_invested_cents: i64 = exchange_row.get("total_invested_cents");
-    let total_extracted_cents: i64 = exchange_row.get("total_extracted_cents");
-    let buy_count: i64 = exchange_row.get("buy_count");
-    let sell_count: i64 = exchange_row.get("sell_count");
-    let sats_stacked_7d: i64 = exchange_row.get("sats_stacked_7d");
-    let usd_invested_7d_cents: i64 = exchange_row.get("usd_invested_7d_cents");
-    let sats_stacked_31d: i64 = exchange_row.get("sats_stacked_31d");
-    let usd_invested_31d_cents: i64 = exchange_row.get("usd_invested_31d_cents");
-    let total_onchain_fees_paid_sats: i64 = fees_row.get("total_onchain_fees_paid");
-
-    let current_sats = total_bought_sats - total_sold_sats - total_onchain_fees_paid_sats;
-    let total_sats_stacked = total_bought_sats;
-    let total_sats_spent = total_sold_sats + total_onchain_fees_paid_sats;
-
-    let avg_buy_price = if buy_count > 0 && total_bought_sats > 0 {
-        Some((total_invested_cents as f64 / 100.0) / (total_bought_sats as f64 / 100_000_000.0))
+    let gains = calculate_realized_gains(
+        pool,
+        filter.tag_ids.as_deref(),
+        filter.start_date,
+        filter.as_of,
+    )
+    .await?;
+
+    let current_sats = opening_sats + totals.net_sats();
+    let total_sats_stacked = totals.bought_sats;
+    let total_sats_spent = totals.sold_sats
+        + totals.onchain_fees_sats
+        + totals.lightning_spent_sats()
+        + totals.spent_on_goods_sats;
+
+    let avg_buy_price = if totals.buy_count > 0 && totals.bought_sats > 0 {
+        Some((totals.invested_cents as f64 / 100.0) / (totals.bought_sats as f64 / 100_000_000.0))
     } else {
         None
     };
 
-    let avg_sell_price = if sell_count > 0 && total_sold_sats > 0 {
-        Some((total_extracted_cents as f64 / 100.0) / (total_sold_sats as f64 / 100_000_000.0))
+    let avg_sell_price = if totals.sell_count > 0 && totals.sold_sats > 0 {
+        Some((totals.extracted_cents as f64 / 100.0) / (totals.sold_sats as f64 / 100_000_000.0))
     } else {
         None
     };
 
-    let overview_metrics = OverviewMetrics {
+    Ok(OverviewMetrics {
         current_sats,
         total_sats_stacked,
         avg_buy_price,
-        total_invested_cents,
+        total_invested_cents: totals.invested_cents,
         avg_sell_price,
-        fiat_extracted_cents: total_extracted_cents,
+        fiat_extracted_cents: totals.extracted_cents,
         total_sats_spent,
-        total_onchain_fees_paid_sats,
-        sats_stacked_7d,
-        usd_invested_7d_cents,
-        sats_stacked_31d,
-        usd_invested_31d_cents,
+        total_sats_spent_on_goods: totals.spent_on_goods_sats,
+        total_spending_fiat_cents: totals.spending_fiat_cents,
+        realized_gain_cents: gains.realized_gain_cents,
+        unpriced_disposal_count: gains.unpriced_disposal_count,
+        total_onchain_fees_paid_sats: totals.onchain_fees_sats,
+        net_adjustment_sats: totals.net_adjustment_sats,
+        total_lightning_sent_msats: totals.lightning_sent_msats,
+        total_lightning_received_msats: totals.lightning_received_msats,
+        total_lightning_routing_fees_msats: totals.lightning_routing_fees_msats,
+        sats_stacked_7d: windows_row.get("sats_stacked_7d"),
+        usd_invested_7d_cents: windows_row.get("usd_invested_7d_cents"),
+        sats_stacked_31d: windows_row.get("sats_stacked_31d"),
+        usd_invested_31d_cents: windows_row.get("usd_invested_31d_cents"),
+        start_date: filter.start_date,
+        as_of: filter.as_of,
+    })
+}
+
+/// Exchange activity grouped by provider ("coinbase", "river", ...) or by account, to compare
+/// what each one costs. Respects the period and tags of `filter` like `get_overview_metrics`.
+#[tauri::command]
+pub async fn get_overview_breakdown(
+    pool: State<'_, SqlitePool>,
+    group_by: String,
+    filter: Option<OverviewFilter>,
+) -> Result<Vec<OverviewBreakdown>, String> {
+    // Only whitelisted expressions are ever interpolated into the query
+    let (group_key, group_label) = match group_by.as_str() {
+        "provider" => ("events.provider", "events.provider"),
+        "account" => ("events.account_id", "COALESCE(accounts.name, 'No account')"),
+        _ => return Err("Invalid grouping. Use 'provider' or 'account'".to_string()),
     };
 
-    println!("Calculated overview metrics: {:?}", overview_metrics);
-    Ok(overview_metrics)
+    let filter = filter.unwrap_or_default();
+    let tag_filter = tag_filter_json(filter.tag_ids.as_deref());
+
+    let breakdown_sql = format!(
+        r#"
+        SELECT
+            {group_key} as group_key,
+            {group_label} as group_label,
+            COUNT(*) as transaction_count,
+            COALESCE(SUM(CASE WHEN events.transaction_type = 'buy' THEN events.amount_sats ELSE 0 END), 0) as sats_acquired,
+            COALESCE(SUM(CASE WHEN events.transaction_type = 'sell' THEN events.amount_sats ELSE 0 END), 0) as sats_sold,
+            COALESCE(SUM(CASE WHEN events.transaction_type = 'buy' THEN events.subtotal_cents ELSE 0 END), 0) as fiat_invested_cents,
+            COALESCE(SUM(CASE WHEN events.transaction_type = 'sell' THEN events.subtotal_cents ELSE 0 END), 0) as fiat_extracted_cents,
+            COALESCE(SUM(CASE WHEN events.transaction_type = 'buy' AND events.subtotal_cents IS NOT NULL THEN events.amount_sats ELSE 0 END), 0) as priced_sats_acquired,
+            COALESCE(SUM(events.fee_cents), 0) as fiat_fees_cents
+        FROM ({UNIFIED_EVENTS_SQL}) AS events
+        LEFT JOIN accounts ON accounts.id = events.account_id
+        WHERE events.record_type = 'exchange_transaction' AND {event_filter}
+        GROUP BY {group_key}
+        ORDER BY fiat_invested_cents DESC
+        "#,
+        event_filter = event_filter_sql("events", "exchange_transaction")
+    );
+
+    let rows = bind_event_filter(sqlx::query(&breakdown_sql), filter.start_date, filter.as_of, &tag_filter)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let breakdown: Vec<OverviewBreakdown> = rows
+        .iter()
+        .map(|row| {
+            let fiat_invested_cents: i64 = row.get("fiat_invested_cents");
+            let fiat_extracted_cents: i64 = row.get("fiat_extracted_cents");
+            let fiat_fees_cents: i64 = row.get("fiat_fees_cents");
+            let priced_sats_acquired: i64 = row.get("priced_sats_acquired");
+            let volume_cents = fiat_invested_cents + fiat_extracted_cents;
+
+            OverviewBreakdown {
+                group_key: row.get("group_key"),
+                group_label: row.get("group_label"),
+                transaction_count: row.get("transaction_count"),
+                sats_acquired: row.get("sats_acquired"),
+                sats_sold: row.get("sats_sold"),
+                fiat_invested_cents,
+                fiat_extracted_cents,
+                avg_buy_price: if priced_sats_acquired > 0 {
+                    Some((fiat_invested_cents as f64 / 100.0) / (priced_sats_acquired as f64 / 100_000_000.0))
+                } else {
+                    None
+                },
+                fiat_fees_cents,
+                effective_fee_percent: if volume_cents > 0 {
+                    Some(fiat_fees_cents as f64 / volume_cents as f64 * 100.0)
+                } else {
+                    None
+                },
+            }
+        })
+        .collect();
+
+    println!("Calculated overview breakdown by {}: {} groups", group_by, breakdown.len());
+    Ok(breakdown)
 }
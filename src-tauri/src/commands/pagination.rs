@@ -0,0 +1,57 @@
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteRow};
+use sqlx::Row;
+
+/// Position just past the last row of a page, as the raw stored (timestamp, id) text so the
+/// cursor never goes through a lossy date round trip.
+pub(crate) type Cursor = (String, String);
+
+/// Keyset condition for listings ordered by `timestamp DESC, id DESC`, bound with `bind_cursor`.
+///
+/// The first page gets no row-value comparison at all: an `? IS NULL OR ...` condition would
+/// keep SQLite from seeking the index and every deep page would scan the rows before it.
+pub(crate) fn keyset_sql(cursor: &Option<Cursor>, timestamp_column: &str, id_column: &str) -> String {
+    match cursor {
+        Some(_) => format!("({timestamp_column}, {id_column}) < (?, ?)"),
+        None => "1 = 1".to_string(),
+    }
+}
+
+/// Binds the parameters of the matching `keyset_sql` condition.
+pub(crate) fn bind_cursor<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    cursor: &'q Option<Cursor>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    match cursor {
+        Some((timestamp, id)) => query.bind(timestamp).bind(id),
+        None => query,
+    }
+}
+
+/// Keyset pages need at least one row, otherwise no cursor can ever be returned.
+pub(crate) fn validate_page_size(page_size: u32) -> Result<(), String> {
+    if page_size == 0 {
+        return Err("Page size must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/// Opaque cursor pointing just past `row`, read from its `timestamp` and `id` columns.
+pub(crate) fn encode_cursor(row: &SqliteRow) -> String {
+    let timestamp: String = row.get("timestamp");
+    let id: String = row.get("id");
+    format!("{}|{}", timestamp, id)
+}
+
+/// Splits a cursor from `encode_cursor` into its parts. `None` starts at the top.
+pub(crate) fn decode_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, String> {
+    match cursor {
+        None => Ok(None),
+        Some(cursor) => {
+            let (timestamp, id) = cursor
+                .rsplit_once('|')
+                .ok_or_else(|| format!("Invalid cursor: {}", cursor))?;
+            Ok(Some((timestamp.to_string(), id.to_string())))
+        }
+    }
+}
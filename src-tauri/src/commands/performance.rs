@@ -0,0 +1,314 @@
+use crate::commands::price_history::{load_daily_closes, price_at};
+use crate::models::performance::PerformanceMetrics;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use tauri::State;
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// One event as seen by an investor: the change in holdings and the fiat that went in (negative)
+/// or came out (positive).
+pub(crate) struct Flow {
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) kind: String, // "buy", "sell", "spend", "fee", "adjustment" or "lightning"
+    pub(crate) msats_delta: i64,
+    pub(crate) cash_cents: Option<i64>, // None when no fiat value was recorded
+}
+
+/// Every event up to `end`, oldest first, for replaying holdings over time.
+///
+/// Lightning payments and balance adjustments move sats in or out without a fiat price, so they
+/// have no cash amount; their routing fees are separate `fee` flows.
+pub(crate) async fn load_flows(pool: &SqlitePool, end: DateTime<Utc>) -> Result<Vec<Flow>, String> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM (
+            SELECT timestamp, type as kind,
+                CASE WHEN type = 'buy' THEN amount_sats ELSE -amount_sats END * 1000 as msats_delta,
+                CASE WHEN type = 'buy'
+                    THEN -(COALESCE(subtotal_cents, 0) + COALESCE(fee_cents, 0))
+                    ELSE COALESCE(subtotal_cents, 0) - COALESCE(fee_cents, 0)
+                END as cash_cents
+            FROM exchange_transactions
+            UNION ALL
+            SELECT timestamp, 'spend', -amount_sats * 1000, fiat_value_cents FROM spending_events
+            UNION ALL
+            SELECT timestamp, 'fee', -amount_sats * 1000, 0 FROM onchain_fees
+            UNION ALL
+            SELECT timestamp, 'adjustment', amount_sats * 1000, NULL FROM balance_adjustments
+            UNION ALL
+            SELECT timestamp, 'lightning',
+                CASE WHEN direction = 'incoming' THEN amount_msats ELSE -amount_msats END,
+                NULL
+            FROM lightning_payments
+            UNION ALL
+            SELECT timestamp, 'fee', -routing_fee_msats, 0 FROM lightning_payments WHERE routing_fee_msats != 0
+        )
+        WHERE timestamp <= ?
+        ORDER BY timestamp ASC
+        "#
+    )
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Flow {
+            timestamp: row.get("timestamp"),
+            kind: row.get("kind"),
+            msats_delta: row.get("msats_delta"),
+            cash_cents: row.get("cash_cents"),
+        })
+        .collect())
+}
+
+fn sats_value_cents(msats: i64, price_cents: i64) -> i64 {
+    (msats as f64 / 1000.0 * price_cents as f64 / 100_000_000.0).round() as i64
+}
+
+/// The fiat that went in or came out with a flow. Flows without a recorded fiat value are valued
+/// at the stored price of their day, or count as zero when there is none.
+fn flow_cash_cents(flow: &Flow, closes: &BTreeMap<NaiveDate, i64>) -> i64 {
+    flow.cash_cents.unwrap_or_else(|| {
+        price_at(closes, flow.timestamp).map_or(0, |price| -sats_value_cents(flow.msats_delta, price))
+    })
+}
+
+fn years_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / 86_400.0 / DAYS_PER_YEAR
+}
+
+/// Annualized internal rate of return of dated cash flows, or None when it has no solution.
+fn xirr(cash_flows: &[(DateTime<Utc>, f64)]) -> Option<f64> {
+    let first = cash_flows.first()?.0;
+    let has_outflow = cash_flows.iter().any(|(_, amount)| *amount < 0.0);
+    let has_inflow = cash_flows.iter().any(|(_, amount)| *amount > 0.0);
+    if !has_outflow || !has_inflow {
+        return None;
+    }
+
+    let npv = |rate: f64| -> f64 {
+        cash_flows
+            .iter()
+            .map(|(date, amount)| amount / (1.0 + rate).powf(years_between(first, *date)))
+            .sum()
+    };
+    let npv_derivative = |rate: f64| -> f64 {
+        cash_flows
+            .iter()
+            .map(|(date, amount)| {
+                let years = years_between(first, *date);
+                -years * amount / (1.0 + rate).powf(years + 1.0)
+            })
+            .sum()
+    };
+
+    // Newton's method converges quickly for typical stacking histories
+    let mut rate = 0.1;
+    for _ in 0..100 {
+        let value = npv(rate);
+        let derivative = npv_derivative(rate);
+        if derivative == 0.0 || !derivative.is_finite() {
+            break;
+        }
+        let next = rate - value / derivative;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+        if (next - rate).abs() < 1e-10 {
+            return Some(next);
+        }
+        rate = next;
+    }
+
+    // Fall back to bisection, NPV decreases with the rate for outflow-first histories
+    let (mut low, mut high) = (-0.9999, 100.0);
+    if npv(low).signum() == npv(high).signum() {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+/// Money-weighted (XIRR), simple and time-weighted returns over a period.
+///
+/// Buys are cash outflows, sells and spending are inflows, and holdings at `end_date` are the
+/// terminal value at `price_usd` (or the stored price of that day when no price is supplied).
+/// With a `start_date`, holdings carried into the period count as an outflow at the start price.
+/// The time-weighted return values holdings at the stored daily price of every flow day.
+///
+/// Flows without a recorded fiat value are external flows valued at the stored price of their
+/// day: incoming lightning payments and positive adjustments count as money put in, outgoing
+/// payments and spending without a fiat value as money taken out, so neither shows up as
+/// return. Fees, including lightning routing fees, are losses.
+#[tauri::command]
+pub async fn get_performance_metrics(
+    pool: State<'_, SqlitePool>,
+    price_usd: Option<f64>,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+) -> Result<PerformanceMetrics, String> {
+    let end = end_date.unwrap_or_else(Utc::now);
+    if let Some(start) = start_date {
+        if start >= end {
+            return Err("Start date must be before the end date".to_string());
+        }
+    }
+
+    let flows = load_flows(pool.inner(), end).await?;
+
+    let first_day = start_date
+        .or_else(|| flows.first().map(|flow| flow.timestamp))
+        .map(|date| date.date_naive());
+    let closes = load_daily_closes(pool.inner(), first_day, Some(end.date_naive())).await?;
+
+    let end_price_cents = match price_usd {
+        Some(price) => (price * 100.0).round() as i64,
+        None => price_at(&closes, end).ok_or_else(|| {
+            format!("No price supplied and no stored price for {}", end.date_naive())
+        })?,
+    };
+
+    // Holdings carried into the period
+    let starting_msats: i64 = match start_date {
+        Some(start) => flows
+            .iter()
+            .filter(|flow| flow.timestamp < start)
+            .map(|flow| flow.msats_delta)
+            .sum(),
+        None => 0,
+    };
+    let starting_value_cents = match start_date {
+        Some(start) if starting_msats != 0 => {
+            let start_price = price_at(&closes, start)
+                .ok_or_else(|| format!("No stored price for {}", start.date_naive()))?;
+            sats_value_cents(starting_msats, start_price)
+        }
+        _ => 0,
+    };
+
+    // Each flow in the period with its resolved cash amount
+    let period_flows: Vec<(&Flow, i64)> = flows
+        .iter()
+        .filter(|flow| start_date.is_none_or(|start| flow.timestamp >= start))
+        .map(|flow| (flow, flow_cash_cents(flow, &closes)))
+        .collect();
+
+    let ending_msats =
+        starting_msats + period_flows.iter().map(|(flow, _)| flow.msats_delta).sum::<i64>();
+    let terminal_value_cents = sats_value_cents(ending_msats, end_price_cents);
+    let invested_cents: i64 = period_flows
+        .iter()
+        .filter(|(_, cash_cents)| *cash_cents < 0)
+        .map(|(_, cash_cents)| -cash_cents)
+        .sum();
+    let extracted_cents: i64 = period_flows
+        .iter()
+        .filter(|(_, cash_cents)| *cash_cents > 0)
+        .map(|(_, cash_cents)| cash_cents)
+        .sum();
+
+    let capital_cents = invested_cents + starting_value_cents;
+    let net_profit_cents = terminal_value_cents + extracted_cents - capital_cents;
+    let simple_roi_percent = if capital_cents > 0 {
+        Some(net_profit_cents as f64 / capital_cents as f64 * 100.0)
+    } else {
+        None
+    };
+
+    // XIRR over the period's dated cash flows
+    let period_start = start_date.or_else(|| period_flows.first().map(|(flow, _)| flow.timestamp));
+    let mut cash_flows: Vec<(DateTime<Utc>, f64)> = Vec::new();
+    if let Some(start) = start_date.filter(|_| starting_value_cents != 0) {
+        cash_flows.push((start, -(starting_value_cents as f64)));
+    }
+    cash_flows.extend(
+        period_flows
+            .iter()
+            .filter(|(_, cash_cents)| *cash_cents != 0)
+            .map(|(flow, cash_cents)| (flow.timestamp, *cash_cents as f64)),
+    );
+    if terminal_value_cents != 0 {
+        cash_flows.push((end, terminal_value_cents as f64));
+    }
+    let xirr_percent = xirr(&cash_flows).map(|rate| rate * 100.0);
+
+    // Time-weighted return, chaining the returns between days with external cash flows
+    let mut flow_days: Vec<(NaiveDate, i64, i64)> = Vec::new(); // (day, msats delta, cash in)
+    for (flow, cash_cents) in &period_flows {
+        let day = flow.timestamp.date_naive();
+        match flow_days.last_mut() {
+            Some(last) if last.0 == day => {
+                last.1 += flow.msats_delta;
+                last.2 -= cash_cents;
+            }
+            _ => flow_days.push((day, flow.msats_delta, -cash_cents)),
+        }
+    }
+
+    let mut unpriced_flow_days = 0;
+    let mut growth = 1.0;
+    let mut holdings_msats = starting_msats;
+    let mut value_after_flows = starting_value_cents as f64;
+    for (day, msats_delta, cash_in_cents) in &flow_days {
+        let Some(price_cents) = closes.range(..=*day).next_back().map(|(_, close)| *close) else {
+            unpriced_flow_days += 1;
+            holdings_msats += msats_delta;
+            continue;
+        };
+
+        let value_before = sats_value_cents(holdings_msats, price_cents) as f64;
+        if value_after_flows > 0.0 {
+            growth *= value_before / value_after_flows;
+        }
+
+        holdings_msats += msats_delta;
+        value_after_flows = value_before + *cash_in_cents as f64;
+    }
+    if value_after_flows > 0.0 {
+        growth *= terminal_value_cents as f64 / value_after_flows;
+    }
+
+    let (twr_percent, twr_annualized_percent) = match period_start {
+        Some(period_start) if unpriced_flow_days == 0 && !flow_days.is_empty() => {
+            let years = years_between(period_start, end);
+            let annualized = if years >= 1.0 {
+                Some((growth.powf(1.0 / years) - 1.0) * 100.0)
+            } else {
+                None
+            };
+            (Some((growth - 1.0) * 100.0), annualized)
+        }
+        _ => (None, None),
+    };
+
+    let metrics = PerformanceMetrics {
+        start_date,
+        end_date: end,
+        starting_sats: starting_msats / 1000,
+        starting_value_cents,
+        ending_sats: ending_msats / 1000,
+        terminal_value_cents,
+        invested_cents,
+        extracted_cents,
+        net_profit_cents,
+        simple_roi_percent,
+        xirr_percent,
+        twr_percent,
+        twr_annualized_percent,
+        unpriced_flow_days,
+    };
+
+    println!("Calculated performance metrics: {:?}", metrics);
+    Ok(metrics)
+}
@@ -0,0 +1,228 @@
+use crate::models::price_history::{DailyPrice, PriceHistoryImportResult};
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use tauri::State;
+
+/// How far back a missing day may fall back to an earlier stored price.
+const MAX_PRICE_GAP_DAYS: u64 = 7;
+
+/// Daily close prices between two days, inclusive, keyed by UTC day.
+pub(crate) async fn load_daily_closes(
+    pool: &SqlitePool,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<BTreeMap<NaiveDate, i64>, String> {
+    // Reach back far enough that the first day can still fall back to an earlier price
+    let from = from.and_then(|from| from.checked_sub_days(Days::new(MAX_PRICE_GAP_DAYS)));
+
+    let rows = sqlx::query(
+        "SELECT date, close_cents FROM price_history
+        WHERE (? IS NULL OR date >= ?) AND (? IS NULL OR date <= ?)
+        ORDER BY date ASC"
+    )
+    .bind(from.map(|d| d.to_string()))
+    .bind(from.map(|d| d.to_string()))
+    .bind(to.map(|d| d.to_string()))
+    .bind(to.map(|d| d.to_string()))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut closes = BTreeMap::new();
+    for row in &rows {
+        let date: String = row.get("date");
+        if let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            closes.insert(date, row.get::<i64, _>("close_cents"));
+        }
+    }
+
+    Ok(closes)
+}
+
+/// Market price in cents per BTC at `timestamp`: the close of its UTC day, or of the latest
+/// earlier day within `MAX_PRICE_GAP_DAYS` when that day is missing.
+pub(crate) fn price_at(closes: &BTreeMap<NaiveDate, i64>, timestamp: DateTime<Utc>) -> Option<i64> {
+    let day = timestamp.date_naive();
+    let earliest = day.checked_sub_days(Days::new(MAX_PRICE_GAP_DAYS))?;
+    closes
+        .range(earliest..=day)
+        .next_back()
+        .map(|(_, close_cents)| *close_cents)
+}
+
+pub(crate) async fn upsert_daily_price(
+    pool: &SqlitePool,
+    date: NaiveDate,
+    open_cents: Option<i64>,
+    high_cents: Option<i64>,
+    low_cents: Option<i64>,
+    close_cents: i64,
+    source: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO price_history (date, open_cents, high_cents, low_cents, close_cents, source, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(date) DO UPDATE SET
+            open_cents = excluded.open_cents,
+            high_cents = excluded.high_cents,
+            low_cents = excluded.low_cents,
+            close_cents = excluded.close_cents,
+            source = excluded.source,
+            updated_at = excluded.updated_at"
+    )
+    .bind(date.to_string())
+    .bind(open_cents)
+    .bind(high_cents)
+    .bind(low_cents)
+    .bind(close_cents)
+    .bind(source)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+fn parse_price_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.with_timezone(&Utc).date_naive()))
+        .or_else(|| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok().map(|dt| dt.date()))
+        .or_else(|| NaiveDate::parse_from_str(value, "%m/%d/%Y").ok())
+        .or_else(|| {
+            // Unix timestamps, in seconds or milliseconds
+            value.parse::<i64>().ok().and_then(|ts| {
+                let seconds = if ts > 100_000_000_000 { ts / 1000 } else { ts };
+                DateTime::from_timestamp(seconds, 0).map(|dt| dt.date_naive())
+            })
+        })
+}
+
+fn parse_price_cents(value: &str) -> Option<i64> {
+    let cleaned = value.trim().replace(['$', ','], "");
+    cleaned
+        .parse::<f64>()
+        .ok()
+        .filter(|usd| *usd > 0.0)
+        .map(|usd| (usd * 100.0).round() as i64)
+}
+
+/// Imports daily prices from a CSV with a date column and a close (or price) column. Open, high
+/// and low columns are picked up when present. Existing days are overwritten.
+#[tauri::command]
+pub async fn import_price_history(
+    pool: State<'_, SqlitePool>,
+    file_path: String,
+) -> Result<PriceHistoryImportResult, String> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read file '{}': {}", file_path, e))?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .clone();
+    let column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|h| names.iter().any(|name| h.trim().eq_ignore_ascii_case(name)))
+    };
+
+    let date_column = column(&["date", "day", "timestamp", "time", "snapped_at"])
+        .ok_or("Price CSV is missing a date column")?;
+    let close_column = column(&["close", "price", "close_usd", "price_usd", "adj close"])
+        .ok_or("Price CSV is missing a close or price column")?;
+    let open_column = column(&["open"]);
+    let high_column = column(&["high"]);
+    let low_column = column(&["low"]);
+
+    let mut imported_count = 0;
+    let mut skipped_count = 0;
+    let mut first_date: Option<NaiveDate> = None;
+    let mut last_date: Option<NaiveDate> = None;
+
+    for result in reader.records() {
+        let record = result.map_err(|e| format!("Failed to parse CSV record: {}", e))?;
+        let cents = |index: Option<usize>| index.and_then(|i| record.get(i)).and_then(parse_price_cents);
+
+        let date = record.get(date_column).and_then(parse_price_date);
+        let close_cents = cents(Some(close_column));
+
+        let (Some(date), Some(close_cents)) = (date, close_cents) else {
+            skipped_count += 1;
+            continue;
+        };
+
+        upsert_daily_price(
+            pool.inner(),
+            date,
+            cents(open_column),
+            cents(high_column),
+            cents(low_column),
+            close_cents,
+            "csv",
+        )
+        .await?;
+
+        imported_count += 1;
+        first_date = Some(first_date.map_or(date, |d| d.min(date)));
+        last_date = Some(last_date.map_or(date, |d| d.max(date)));
+    }
+
+    let result = PriceHistoryImportResult {
+        imported_count,
+        skipped_count,
+        first_date,
+        last_date,
+    };
+
+    println!("Imported price history: {:?}", result);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_price_history(
+    pool: State<'_, SqlitePool>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Result<Vec<DailyPrice>, String> {
+    let rows = sqlx::query(
+        "SELECT date, open_cents, high_cents, low_cents, close_cents, source, updated_at
+        FROM price_history
+        WHERE (? IS NULL OR date >= ?) AND (? IS NULL OR date <= ?)
+        ORDER BY date ASC"
+    )
+    .bind(start_date.map(|d| d.to_string()))
+    .bind(start_date.map(|d| d.to_string()))
+    .bind(end_date.map(|d| d.to_string()))
+    .bind(end_date.map(|d| d.to_string()))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let prices = rows
+        .iter()
+        .map(|row| {
+            let date: String = row.get("date");
+            Ok(DailyPrice {
+                date: NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid price date '{}': {}", date, e))?,
+                open_cents: row.get("open_cents"),
+                high_cents: row.get("high_cents"),
+                low_cents: row.get("low_cents"),
+                close_cents: row.get("close_cents"),
+                source: row.get("source"),
+                updated_at: row.get("updated_at"),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    println!("Retrieved {} daily prices", prices.len());
+    Ok(prices)
+}
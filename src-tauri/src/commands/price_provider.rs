@@ -0,0 +1,312 @@
+use crate::commands::api::{default_api_host, http_client, BitcoinPriceResponse};
+use crate::models::settings::{AppSettings, PriceProviderKind};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{Row, SqlitePool};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Where the current BTC price comes from. Every adapter answers with the same
+/// `BitcoinPriceResponse` the hosted proxy returns, so callers never care which one is in use.
+#[derive(Debug, Clone)]
+pub(crate) enum PriceProvider {
+    /// The hosted dprogram proxy, or a self-hosted copy of it.
+    Dprogram { host: String, client: reqwest::Client },
+    /// Any JSON endpoint in the BTCPay, mempool or `{"price": ...}` format.
+    CustomEndpoint { url: String, client: reqwest::Client },
+    /// A static JSON file in any of the endpoint formats.
+    LocalFile { path: String },
+}
+
+impl PriceProvider {
+    /// Network providers get their client from `http_client`, so they cannot be built at all
+    /// in offline mode. The dprogram proxy is only built for a USD base currency.
+    pub(crate) fn from_settings(
+        kind: PriceProviderKind,
+        settings: &AppSettings,
+    ) -> Result<Self, String> {
+        let provider = match kind {
+            PriceProviderKind::Dprogram if settings.base_currency != "USD" => {
+                return Err(format!("The dprogram proxy has no BTC/{} price", settings.base_currency));
+            }
+            PriceProviderKind::Dprogram => PriceProvider::Dprogram {
+                host: settings.price_api_host.clone().unwrap_or_else(default_api_host),
+                client: http_client(settings)?,
+            },
+            PriceProviderKind::CustomEndpoint => PriceProvider::CustomEndpoint {
+                url: settings
+                    .price_endpoint_url
+                    .clone()
+                    .ok_or_else(|| "No custom price endpoint configured".to_string())?,
+                client: http_client(settings)?,
+            },
+            PriceProviderKind::LocalFile => PriceProvider::LocalFile {
+                path: settings
+                    .price_file_path
+                    .clone()
+                    .ok_or_else(|| "No local price file configured".to_string())?,
+            },
+        };
+
+        Ok(provider)
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            PriceProvider::Dprogram { .. } => "dprogram",
+            PriceProvider::CustomEndpoint { .. } => "custom_endpoint",
+            PriceProvider::LocalFile { .. } => "local_file",
+        }
+    }
+
+    pub(crate) fn needs_network(&self) -> bool {
+        !matches!(self, PriceProvider::LocalFile { .. })
+    }
+
+    /// Current price in `currency`. The dprogram proxy only quotes USD.
+    pub(crate) async fn fetch_price(&self, currency: &str) -> Result<BitcoinPriceResponse, String> {
+        match self {
+            PriceProvider::Dprogram { host, client } => {
+                if currency != "USD" {
+                    return Err(format!("The dprogram proxy has no BTC/{} price", currency));
+                }
+                let price_data = fetch_dprogram_price(client, host).await?;
+                if !price_data.success || price_data.price.is_none() {
+                    return Err(price_data
+                        .error
+                        .clone()
+                        .unwrap_or_else(|| "Price API returned no price".to_string()));
+                }
+                Ok(price_data)
+            }
+            PriceProvider::CustomEndpoint { url, client } => {
+                let url = url.replace("{currency}", currency);
+                let json = fetch_json(client, &url).await?;
+                price_response_from_json(&json, currency)
+            }
+            PriceProvider::LocalFile { path } => {
+                let content = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read price file: {}", e))?;
+                let json: Value = serde_json::from_str(&content)
+                    .map_err(|e| format!("Failed to parse price file: {}", e))?;
+                price_response_from_json(&json, currency)
+            }
+        }
+        .map(|mut price_data| {
+            price_data.source = Some(self.name().to_string());
+            price_data
+        })
+    }
+
+    /// `fetch_price` with up to `MAX_ATTEMPTS` tries, doubling the pause between them.
+    pub(crate) async fn fetch_price_with_retries(
+        &self,
+        currency: &str,
+    ) -> Result<BitcoinPriceResponse, String> {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            match self.fetch_price(currency).await {
+                Ok(price_data) => return Ok(price_data),
+                // A local file will not fix itself between attempts
+                Err(e) if attempt >= MAX_ATTEMPTS || !self.needs_network() => return Err(e),
+                Err(e) => {
+                    println!(
+                        "Price attempt {} from {} failed, retrying in {:?}: {}",
+                        attempt,
+                        self.name(),
+                        backoff,
+                        e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// The providers to try in order: the main one, then the fallbacks. Providers that are not
+/// configured or need the network in offline mode are left out.
+pub(crate) fn provider_chain(settings: &AppSettings) -> Vec<PriceProvider> {
+    let mut kinds = vec![settings.price_provider];
+    for kind in &settings.price_fallback_providers {
+        if !kinds.contains(kind) {
+            kinds.push(*kind);
+        }
+    }
+
+    kinds
+        .into_iter()
+        .filter_map(|kind| match PriceProvider::from_settings(kind, settings) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                println!("Skipping price provider {:?}: {}", kind, e);
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) async fn save_cached_price(
+    pool: &SqlitePool,
+    currency: &str,
+    source: &str,
+    price_data: &BitcoinPriceResponse,
+) -> Result<(), String> {
+    let Some(price) = price_data.price else {
+        return Ok(());
+    };
+
+    sqlx::query(
+        "INSERT INTO price_cache (currency, price, percent_change_24hr, source, fetched_at) VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(currency) DO UPDATE SET price = excluded.price, percent_change_24hr = excluded.percent_change_24hr,
+            source = excluded.source, fetched_at = excluded.fetched_at"
+    )
+    .bind(currency)
+    .bind(price)
+    .bind(price_data.percent_change_24hr)
+    .bind(source)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(())
+}
+
+/// The persisted quote for `currency`, marked as cached and stale with its age in milliseconds.
+pub(crate) async fn load_cached_price(
+    pool: &SqlitePool,
+    currency: &str,
+) -> Result<Option<BitcoinPriceResponse>, String> {
+    let row = sqlx::query(
+        "SELECT price, percent_change_24hr, source, fetched_at FROM price_cache WHERE currency = ?"
+    )
+    .bind(currency)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(row.map(|row| {
+        let fetched_at: DateTime<Utc> = row.get("fetched_at");
+        BitcoinPriceResponse {
+            success: true,
+            price: Some(row.get("price")),
+            percent_change_24hr: row.get("percent_change_24hr"),
+            cached: Some(true),
+            cache_age: Some((Utc::now() - fetched_at).num_milliseconds().max(0) as u64),
+            timestamp: Some(fetched_at.timestamp_millis() as u64),
+            stale: Some(true),
+            message: None,
+            error: None,
+            source: Some(row.get("source")),
+        }
+    }))
+}
+
+async fn fetch_dprogram_price(
+    client: &reqwest::Client,
+    host: &str,
+) -> Result<BitcoinPriceResponse, String> {
+    let url = format!("{}/api/proxy/sat-tracker/bitcoin-price", host);
+    let response = client
+        .get(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch Bitcoin price: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP error: {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+async fn fetch_json(client: &reqwest::Client, url: &str) -> Result<Value, String> {
+    let response = client
+        .get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch Bitcoin price: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP error: {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Reads a number that may also be sent as a string, as BTCPay does for rates.
+fn json_number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Finds the price for `currency` in the formats self-hosted price sources use:
+/// - `{"price": 65000, "percentChange24hr": 1.2}` (the proxy's own shape)
+/// - `{"time": 1700000000, "USD": 65000, "EUR": 60000}` (mempool `/api/v1/prices`)
+/// - `[{"currencyPair": "BTC_USD", "rate": "65000"}]` (BTCPay rates), optionally under `"data"`
+fn extract_price(json: &Value, currency: &str) -> Option<f64> {
+    if let Some(price) = json.get("price").and_then(json_number) {
+        return Some(price);
+    }
+    if let Some(price) = json
+        .get(currency)
+        .or_else(|| json.get(currency.to_lowercase()))
+        .and_then(json_number)
+    {
+        return Some(price);
+    }
+    if let Some(rates) = json.as_array() {
+        let pair = format!("BTC_{}", currency);
+        return rates.iter().find_map(|rate| {
+            let matches = rate.get("currencyPair").and_then(Value::as_str) == Some(pair.as_str())
+                || rate.get("code").and_then(Value::as_str) == Some(currency);
+            if matches {
+                rate.get("rate").and_then(json_number)
+            } else {
+                None
+            }
+        });
+    }
+    json.get("data").and_then(|data| extract_price(data, currency))
+}
+
+fn price_response_from_json(json: &Value, currency: &str) -> Result<BitcoinPriceResponse, String> {
+    let price = extract_price(json, currency)
+        .filter(|price| *price > 0.0)
+        .ok_or_else(|| format!("No BTC/{} price found in the response", currency))?;
+
+    Ok(BitcoinPriceResponse {
+        success: true,
+        price: Some(price),
+        percent_change_24hr: json.get("percentChange24hr").and_then(json_number),
+        cached: Some(false),
+        cache_age: None,
+        // mempool sends `time` in seconds, everything else uses milliseconds
+        timestamp: json
+            .get("time")
+            .and_then(Value::as_u64)
+            .map(|seconds| seconds * 1000)
+            .or_else(|| json.get("timestamp").and_then(Value::as_u64))
+            .or_else(|| Some(Utc::now().timestamp_millis() as u64)),
+        stale: Some(false),
+        message: None,
+        error: None,
+        source: None,
+    })
+}
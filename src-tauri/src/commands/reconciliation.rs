@@ -0,0 +1,332 @@
+use crate::commands::lightning_payment::msats_to_sats;
+use crate::models::reconciliation::{
+    BalanceAdjustment, CreateReconciliationRequest, Reconciliation,
+};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+use uuid::Uuid;
+
+/// Balance implied by recorded events up to `as_of`.
+///
+/// For the whole stack (`account_id = None`) and for accounts without watch-only wallets this
+/// replays buys, sells, on-chain fees, lightning payments, spending and adjustments. Accounts backed by
+/// watch-only wallets use the imported wallet activity instead, since that is what the wallet
+/// itself reports.
+pub(crate) async fn calculate_implied_balance(
+    pool: &SqlitePool,
+    account_id: Option<&str>,
+    as_of: DateTime<Utc>,
+) -> Result<i64, String> {
+    let wallet_count: i64 = match account_id {
+        Some(account_id) => sqlx::query_scalar("SELECT COUNT(*) FROM wallets WHERE account_id = ?")
+            .bind(account_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?,
+        None => 0,
+    };
+
+    let adjustments_sats: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount_sats), 0) FROM balance_adjustments
+        WHERE timestamp <= ? AND (? IS NULL OR account_id = ?)"
+    )
+    .bind(as_of)
+    .bind(account_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    if wallet_count > 0 {
+        let wallet_sats: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(a.amount_sats), 0)
+            FROM wallet_activity a
+            JOIN wallets w ON w.id = a.wallet_id
+            WHERE w.account_id = ? AND a.timestamp <= ?"
+        )
+        .bind(account_id)
+        .bind(as_of)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        return Ok(wallet_sats + adjustments_sats);
+    }
+
+    let exchange_sats: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(CASE WHEN type = 'buy' THEN amount_sats ELSE -amount_sats END), 0)
+        FROM exchange_transactions
+        WHERE timestamp <= ? AND (? IS NULL OR account_id = ?)"
+    )
+    .bind(as_of)
+    .bind(account_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let onchain_fee_sats: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount_sats), 0) FROM onchain_fees
+        WHERE timestamp <= ? AND (? IS NULL OR account_id = ?)"
+    )
+    .bind(as_of)
+    .bind(account_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let lightning_net_msats: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(CASE WHEN direction = 'incoming' THEN amount_msats ELSE -amount_msats END - routing_fee_msats), 0)
+        FROM lightning_payments
+        WHERE timestamp <= ? AND (? IS NULL OR account_id = ?)"
+    )
+    .bind(as_of)
+    .bind(account_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let spent_sats: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount_sats), 0) FROM spending_events
+        WHERE timestamp <= ? AND (? IS NULL OR account_id = ?)"
+    )
+    .bind(as_of)
+    .bind(account_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(exchange_sats - onchain_fee_sats - spent_sats
+        + adjustments_sats
+        + msats_to_sats(lightning_net_msats))
+}
+
+async fn reconciliation_from_row(
+    pool: &SqlitePool,
+    row: &SqliteRow,
+) -> Result<Reconciliation, String> {
+    let account_id: Option<String> = row.get("account_id");
+    let observed_sats: i64 = row.get("observed_sats");
+    let observed_at: DateTime<Utc> = row.get("observed_at");
+
+    // Recomputed on every read so late imports and edits show up in the discrepancy
+    let implied_sats = calculate_implied_balance(pool, account_id.as_deref(), observed_at).await?;
+
+    Ok(Reconciliation {
+        id: row.get("id"),
+        account_id,
+        observed_sats,
+        observed_at,
+        implied_sats,
+        discrepancy_sats: observed_sats - implied_sats,
+        memo: row.get("memo"),
+        adjustment_id: row.get("adjustment_id"),
+        created_at: row.get("created_at"),
+    })
+}
+
+async fn fetch_reconciliation(pool: &SqlitePool, id: &str) -> Result<Reconciliation, String> {
+    let row = sqlx::query(
+        "SELECT id, account_id, observed_sats, observed_at, memo, adjustment_id, created_at FROM reconciliations WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| "Reconciliation not found".to_string())?;
+
+    reconciliation_from_row(pool, &row).await
+}
+
+#[tauri::command]
+pub async fn create_reconciliation(
+    pool: State<'_, SqlitePool>,
+    request: CreateReconciliationRequest,
+) -> Result<Reconciliation, String> {
+    if request.observed_sats < 0 {
+        return Err("Observed balance cannot be negative".to_string());
+    }
+
+    let id = Uuid::new_v4().to_string();
+
+    sqlx::query(
+        "INSERT INTO reconciliations (id, account_id, observed_sats, observed_at, memo, created_at) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&id)
+    .bind(&request.account_id)
+    .bind(request.observed_sats)
+    .bind(request.observed_at)
+    .bind(&request.memo)
+    .bind(Utc::now())
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let reconciliation = fetch_reconciliation(pool.inner(), &id).await?;
+
+    println!("Created reconciliation: {:?}", reconciliation);
+    Ok(reconciliation)
+}
+
+#[tauri::command]
+pub async fn get_reconciliations(
+    pool: State<'_, SqlitePool>,
+    account_id: Option<String>,
+) -> Result<Vec<Reconciliation>, String> {
+    let rows = sqlx::query(
+        "SELECT id, account_id, observed_sats, observed_at, memo, adjustment_id, created_at
+        FROM reconciliations
+        WHERE (? IS NULL OR account_id = ?)
+        ORDER BY observed_at DESC"
+    )
+    .bind(&account_id)
+    .bind(&account_id)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut reconciliations = Vec::new();
+    for row in &rows {
+        reconciliations.push(reconciliation_from_row(pool.inner(), row).await?);
+    }
+
+    println!("Retrieved {} reconciliations", reconciliations.len());
+    Ok(reconciliations)
+}
+
+#[tauri::command]
+pub async fn delete_reconciliation(pool: State<'_, SqlitePool>, id: String) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM reconciliations WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Reconciliation not found".to_string());
+    }
+
+    println!("Deleted reconciliation with id: {}", id);
+    Ok(())
+}
+
+/// Books the current discrepancy of a reconciliation as an adjustment event at the observation date.
+#[tauri::command]
+pub async fn create_reconciliation_adjustment(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    memo: Option<String>,
+) -> Result<BalanceAdjustment, String> {
+    let reconciliation = fetch_reconciliation(pool.inner(), &id).await?;
+
+    if reconciliation.adjustment_id.is_some() {
+        return Err("This reconciliation has already been booked as an adjustment".to_string());
+    }
+    if reconciliation.discrepancy_sats == 0 {
+        return Err("Observed balance already matches, nothing to adjust".to_string());
+    }
+
+    let adjustment = BalanceAdjustment {
+        id: Uuid::new_v4().to_string(),
+        account_id: reconciliation.account_id.clone(),
+        amount_sats: reconciliation.discrepancy_sats,
+        memo: memo.or_else(|| {
+            Some(format!(
+                "Reconciliation adjustment: observed {} sats, tracked {} sats",
+                reconciliation.observed_sats, reconciliation.implied_sats
+            ))
+        }),
+        timestamp: reconciliation.observed_at,
+        created_at: Utc::now(),
+    };
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    sqlx::query(
+        "INSERT INTO balance_adjustments (id, account_id, amount_sats, memo, timestamp, created_at) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&adjustment.id)
+    .bind(&adjustment.account_id)
+    .bind(adjustment.amount_sats)
+    .bind(&adjustment.memo)
+    .bind(adjustment.timestamp)
+    .bind(adjustment.created_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    // Only links an unbooked reconciliation, so a concurrent call cannot book it twice
+    let result = sqlx::query(
+        "UPDATE reconciliations SET adjustment_id = ? WHERE id = ? AND adjustment_id IS NULL"
+    )
+    .bind(&adjustment.id)
+    .bind(&id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("This reconciliation has already been booked as an adjustment".to_string());
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Created balance adjustment: {:?}", adjustment);
+    Ok(adjustment)
+}
+
+#[tauri::command]
+pub async fn get_balance_adjustments(
+    pool: State<'_, SqlitePool>,
+) -> Result<Vec<BalanceAdjustment>, String> {
+    let rows = sqlx::query(
+        "SELECT id, account_id, amount_sats, memo, timestamp, created_at FROM balance_adjustments ORDER BY timestamp DESC"
+    )
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let adjustments: Vec<BalanceAdjustment> = rows
+        .iter()
+        .map(|row| BalanceAdjustment {
+            id: row.get("id"),
+            account_id: row.get("account_id"),
+            amount_sats: row.get("amount_sats"),
+            memo: row.get("memo"),
+            timestamp: row.get("timestamp"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+
+    println!("Retrieved {} balance adjustments", adjustments.len());
+    Ok(adjustments)
+}
+
+#[tauri::command]
+pub async fn delete_balance_adjustment(
+    pool: State<'_, SqlitePool>,
+    id: String,
+) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM balance_adjustments WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Balance adjustment not found".to_string());
+    }
+
+    println!("Deleted balance adjustment with id: {}", id);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use crate::commands::time_buckets::parse_timezone;
+use crate::models::settings::{AppSettings, PriceProviderKind, SettingsPatch};
+use chrono::{Utc, Weekday};
+use chrono_tz::Tz;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+
+/// Loads the stored settings over the defaults. Unknown keys and values that no longer parse
+/// are skipped, so a bad row never locks the user out of the app.
+pub(crate) async fn load_settings(pool: &SqlitePool) -> Result<AppSettings, String> {
+    let rows = sqlx::query("SELECT key, value FROM settings")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut merged = serde_json::to_value(AppSettings::default())
+        .map_err(|e| format!("Failed to encode settings: {}", e))?;
+
+    for row in &rows {
+        let key: String = row.get("key");
+        let raw: String = row.get("value");
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) else {
+            println!("Ignoring unreadable setting {}: {}", key, raw);
+            continue;
+        };
+        if merged.get(&key).is_none() {
+            println!("Ignoring unknown setting: {}", key);
+            continue;
+        }
+
+        let mut candidate = merged.clone();
+        candidate[key.as_str()] = value;
+        if serde_json::from_value::<AppSettings>(candidate.clone()).is_ok() {
+            merged = candidate;
+        } else {
+            println!("Ignoring invalid setting {}: {}", key, raw);
+        }
+    }
+
+    serde_json::from_value(merged).map_err(|e| format!("Failed to decode settings: {}", e))
+}
+
+/// The explicit timezone when given, otherwise the one from settings.
+pub(crate) async fn resolve_timezone(pool: &SqlitePool, timezone: Option<&str>) -> Result<Tz, String> {
+    match timezone {
+        Some(_) => parse_timezone(timezone),
+        None => parse_timezone(load_settings(pool).await?.timezone.as_deref()),
+    }
+}
+
+pub(crate) fn parse_week_start(week_start: &str) -> Result<Weekday, String> {
+    week_start
+        .parse::<Weekday>()
+        .map_err(|_| format!("Invalid week start: {}", week_start))
+}
+
+fn validate_settings(settings: &AppSettings) -> Result<(), String> {
+    if settings.base_currency.len() != 3
+        || !settings.base_currency.chars().all(|c| c.is_ascii_uppercase())
+    {
+        return Err("Base currency must be a three letter code such as USD".to_string());
+    }
+    parse_timezone(settings.timezone.as_deref())?;
+    parse_week_start(&settings.week_start)?;
+    if let Some(host) = &settings.price_api_host {
+        if !host.starts_with("http://") && !host.starts_with("https://") {
+            return Err("Price API host must start with http:// or https://".to_string());
+        }
+    }
+    if let Some(url) = &settings.price_endpoint_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("Price endpoint URL must start with http:// or https://".to_string());
+        }
+    }
+    if let Some(proxy) = &settings.proxy_url {
+        if !proxy.starts_with("socks5://") && !proxy.starts_with("socks5h://") {
+            return Err("Proxy URL must start with socks5:// or socks5h://".to_string());
+        }
+        reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    }
+    match settings.price_provider {
+        PriceProviderKind::Dprogram => {}
+        PriceProviderKind::CustomEndpoint if settings.price_endpoint_url.is_none() => {
+            return Err("A custom price endpoint needs a URL".to_string());
+        }
+        PriceProviderKind::LocalFile if settings.price_file_path.is_none() => {
+            return Err("A local price file needs a file path".to_string());
+        }
+        PriceProviderKind::CustomEndpoint | PriceProviderKind::LocalFile => {}
+    }
+    if settings.coinbase_grouping_window_seconds < 0 {
+        return Err("Coinbase grouping window cannot be negative".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_settings(pool: State<'_, SqlitePool>) -> Result<AppSettings, String> {
+    load_settings(pool.inner()).await
+}
+
+/// Applies `settings` over the stored settings, then validates and stores the result. Returns
+/// the complete settings that were saved.
+#[tauri::command]
+pub async fn update_settings(
+    pool: State<'_, SqlitePool>,
+    settings: SettingsPatch,
+) -> Result<AppSettings, String> {
+    let mut merged = serde_json::to_value(load_settings(pool.inner()).await?)
+        .map_err(|e| format!("Failed to encode settings: {}", e))?;
+    for (key, value) in settings {
+        if merged.get(&key).is_none() {
+            return Err(format!("Unknown setting: {}", key));
+        }
+        merged[key.as_str()] = value;
+    }
+    let mut settings: AppSettings =
+        serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    settings.base_currency = settings.base_currency.trim().to_uppercase();
+    settings.price_api_host = settings
+        .price_api_host
+        .map(|host| host.trim().trim_end_matches('/').to_string())
+        .filter(|host| !host.is_empty());
+    settings.price_endpoint_url = settings
+        .price_endpoint_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    settings.price_file_path = settings
+        .price_file_path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    settings.proxy_url = settings
+        .proxy_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    validate_settings(&settings)?;
+
+    let values = serde_json::to_value(&settings)
+        .map_err(|e| format!("Failed to encode settings: {}", e))?;
+    let now = Utc::now();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    for (key, value) in values.as_object().into_iter().flatten() {
+        sqlx::query(
+            "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at"
+        )
+        .bind(key)
+        .bind(value.to_string())
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Updated settings: {:?}", settings);
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn reset_settings(pool: State<'_, SqlitePool>) -> Result<AppSettings, String> {
+    sqlx::query("DELETE FROM settings")
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Reset settings to defaults");
+    Ok(AppSettings::default())
+}
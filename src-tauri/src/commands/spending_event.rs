@@ -0,0 +1,214 @@
+use crate::models::spending_event::{
+    CreateSpendingEventRequest, PaginatedSpendingEvents, SpendingEvent, SpendingSummary,
+    UpdateSpendingEventRequest,
+};
+use chrono::Utc;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+use uuid::Uuid;
+
+fn spending_event_from_row(row: &SqliteRow) -> SpendingEvent {
+    SpendingEvent {
+        id: row.get("id"),
+        amount_sats: row.get("amount_sats"),
+        fiat_value_cents: row.get("fiat_value_cents"),
+        merchant: row.get("merchant"),
+        category: row.get("category"),
+        memo: row.get("memo"),
+        timestamp: row.get("timestamp"),
+        created_at: row.get("created_at"),
+        account_id: row.get("account_id"),
+    }
+}
+
+/// Trims free-text labels so "Groceries" and "Groceries " group together.
+fn normalize_label(label: Option<String>) -> Option<String> {
+    label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+}
+
+#[tauri::command]
+pub async fn create_spending_event(
+    pool: State<'_, SqlitePool>,
+    request: CreateSpendingEventRequest,
+) -> Result<SpendingEvent, String> {
+    if request.amount_sats <= 0 {
+        return Err("Spent amount must be positive".to_string());
+    }
+
+    let spending_event = SpendingEvent {
+        id: Uuid::new_v4().to_string(),
+        amount_sats: request.amount_sats,
+        fiat_value_cents: request.fiat_value_cents,
+        merchant: normalize_label(request.merchant),
+        category: normalize_label(request.category),
+        memo: request.memo,
+        timestamp: request.timestamp,
+        created_at: Utc::now(),
+        account_id: request.account_id,
+    };
+
+    sqlx::query(
+        "INSERT INTO spending_events (id, amount_sats, fiat_value_cents, merchant, category, memo, timestamp, created_at, account_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&spending_event.id)
+    .bind(spending_event.amount_sats)
+    .bind(spending_event.fiat_value_cents)
+    .bind(&spending_event.merchant)
+    .bind(&spending_event.category)
+    .bind(&spending_event.memo)
+    .bind(spending_event.timestamp)
+    .bind(spending_event.created_at)
+    .bind(&spending_event.account_id)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    println!("Created spending event: {:?}", spending_event);
+    Ok(spending_event)
+}
+
+#[tauri::command]
+pub async fn get_spending_events(
+    pool: State<'_, SqlitePool>,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedSpendingEvents, String> {
+    let offset = page * page_size;
+
+    let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM spending_events")
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let rows = sqlx::query(
+        "SELECT id, amount_sats, fiat_value_cents, merchant, category, memo, timestamp, created_at, account_id FROM spending_events ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+    )
+    .bind(page_size as i64)
+    .bind(offset as i64)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let spending_events: Vec<SpendingEvent> = rows.iter().map(spending_event_from_row).collect();
+
+    let total_pages = ((total_count as f64) / (page_size as f64)).ceil() as u32;
+    let has_more = (page + 1) * page_size < total_count as u32;
+
+    let result = PaginatedSpendingEvents {
+        spending_events,
+        total_count,
+        page,
+        page_size,
+        total_pages,
+        has_more,
+    };
+
+    println!(
+        "Retrieved {} spending events (page {} of {}, has_more: {})",
+        result.spending_events.len(),
+        page,
+        total_pages,
+        has_more
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn update_spending_event(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    request: UpdateSpendingEventRequest,
+) -> Result<SpendingEvent, String> {
+    if request.amount_sats <= 0 {
+        return Err("Spent amount must be positive".to_string());
+    }
+
+    sqlx::query(
+        "UPDATE spending_events SET amount_sats = ?, fiat_value_cents = ?, merchant = ?, category = ?, memo = ?, timestamp = ?, account_id = ? WHERE id = ?"
+    )
+    .bind(request.amount_sats)
+    .bind(request.fiat_value_cents)
+    .bind(normalize_label(request.merchant))
+    .bind(normalize_label(request.category))
+    .bind(&request.memo)
+    .bind(request.timestamp)
+    .bind(&request.account_id)
+    .bind(&id)
+    .execute(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let row = sqlx::query(
+        "SELECT id, amount_sats, fiat_value_cents, merchant, category, memo, timestamp, created_at, account_id FROM spending_events WHERE id = ?"
+    )
+    .bind(&id)
+    .fetch_one(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let updated_spending_event = spending_event_from_row(&row);
+
+    println!("Updated spending event: {:?}", updated_spending_event);
+    Ok(updated_spending_event)
+}
+
+#[tauri::command]
+pub async fn delete_spending_event(
+    pool: State<'_, SqlitePool>,
+    id: String,
+) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM spending_events WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Spending event not found".to_string());
+    }
+
+    println!("Deleted spending event with id: {}", id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_spending_summary(
+    pool: State<'_, SqlitePool>,
+    group_by: String,
+) -> Result<Vec<SpendingSummary>, String> {
+    // Only whitelisted column names are ever interpolated into the query
+    let column = match group_by.as_str() {
+        "category" => "category",
+        "merchant" => "merchant",
+        _ => return Err("Invalid grouping. Use 'category' or 'merchant'".to_string()),
+    };
+
+    let rows = sqlx::query(&format!(
+        "SELECT
+            COALESCE({column}, 'Uncategorized') as spending_group,
+            COUNT(*) as event_count,
+            COALESCE(SUM(amount_sats), 0) as total_sats,
+            COALESCE(SUM(fiat_value_cents), 0) as total_fiat_cents
+        FROM spending_events
+        GROUP BY spending_group
+        ORDER BY total_sats DESC"
+    ))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let summary = rows
+        .iter()
+        .map(|row| SpendingSummary {
+            group: row.get("spending_group"),
+            event_count: row.get("event_count"),
+            total_sats: row.get("total_sats"),
+            total_fiat_cents: row.get("total_fiat_cents"),
+        })
+        .collect();
+
+    Ok(summary)
+}
@@ -0,0 +1,264 @@
+use crate::commands::price_history::load_daily_closes;
+use crate::models::strategy_simulation::{StrategyComparison, StrategyResult};
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use tauri::State;
+
+const DEFAULT_DIP_MOVING_AVERAGE_DAYS: i64 = 30;
+
+/// Accumulates simulated buys for one strategy.
+struct Simulation {
+    strategy: &'static str,
+    fee_rate: f64,
+    invested_cents: i64,
+    sats_acquired: i64,
+    purchase_count: i64,
+    unpriced_days: i64,
+}
+
+impl Simulation {
+    fn new(strategy: &'static str, fee_rate: f64) -> Self {
+        Simulation {
+            strategy,
+            fee_rate,
+            invested_cents: 0,
+            sats_acquired: 0,
+            purchase_count: 0,
+            unpriced_days: 0,
+        }
+    }
+
+    /// Spends `cents` at `price_cents` per BTC, with the fee taken out of the amount.
+    fn buy(&mut self, cents: i64, price_cents: i64) {
+        if cents <= 0 {
+            return;
+        }
+        let net_cents = cents as f64 / (1.0 + self.fee_rate);
+        self.sats_acquired += (net_cents * 100_000_000.0 / price_cents as f64).round() as i64;
+        self.invested_cents += cents;
+        self.purchase_count += 1;
+    }
+
+    fn result(&self, total_contributions_cents: i64, actual_sats: i64) -> StrategyResult {
+        StrategyResult {
+            strategy: self.strategy.to_string(),
+            fiat_invested_cents: self.invested_cents,
+            fiat_uninvested_cents: total_contributions_cents - self.invested_cents,
+            sats_acquired: self.sats_acquired,
+            purchase_count: self.purchase_count,
+            unpriced_days: self.unpriced_days,
+            avg_price_cents: if self.sats_acquired > 0 {
+                Some((self.invested_cents as f64 * 100_000_000.0 / self.sats_acquired as f64).round() as i64)
+            } else {
+                None
+            },
+            sats_vs_actual_percent: if actual_sats > 0 {
+                (self.sats_acquired - actual_sats) as f64 / actual_sats as f64 * 100.0
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Splits `total` into `parts` amounts that add up exactly, earlier parts taking the remainder.
+fn split_evenly(total: i64, parts: usize) -> Vec<i64> {
+    let parts_i64 = parts.max(1) as i64;
+    (0..parts_i64)
+        .map(|i| total / parts_i64 + if i < total % parts_i64 { 1 } else { 0 })
+        .collect()
+}
+
+/// Buys each scheduled amount at the close of its day, or of the next day with a stored price.
+fn simulate_schedule(
+    simulation: &mut Simulation,
+    schedule: &[(NaiveDate, i64)],
+    closes: &BTreeMap<NaiveDate, i64>,
+    end: NaiveDate,
+) {
+    for (day, cents) in schedule {
+        if let Some((priced_day, price_cents)) = closes.range(*day..=end).next() {
+            simulation.buy(*cents, *price_cents);
+            if priced_day != day {
+                simulation.unpriced_days += 1;
+            }
+        } else {
+            simulation.unpriced_days += 1;
+        }
+    }
+}
+
+/// Compares the sats the user actually received with what the same fiat contributions would have
+/// bought under alternative strategies, using the stored daily closes.
+///
+/// The period runs from the first to the last buy unless given. Every simulated buy pays the
+/// user's actual average fee rate so that only timing differs between strategies. The dip
+/// strategy accrues the weekly DCA amount as cash and deploys all of it whenever the close is
+/// below its moving average; cash still waiting at the end is reported as uninvested.
+#[tauri::command]
+pub async fn get_strategy_comparison(
+    pool: State<'_, SqlitePool>,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+    dip_moving_average_days: Option<i64>,
+) -> Result<StrategyComparison, String> {
+    let moving_average_days = dip_moving_average_days.unwrap_or(DEFAULT_DIP_MOVING_AVERAGE_DAYS);
+    if moving_average_days < 1 {
+        return Err("Moving average window must be at least one day".to_string());
+    }
+
+    let row = sqlx::query(
+        r#"
+        SELECT
+            COUNT(*) as buy_count,
+            COALESCE(SUM(amount_sats), 0) as sats,
+            COALESCE(SUM(COALESCE(subtotal_cents, 0)), 0) as subtotal_cents,
+            COALESCE(SUM(COALESCE(fee_cents, 0)), 0) as fee_cents,
+            MIN(timestamp) as first_buy,
+            MAX(timestamp) as last_buy
+        FROM exchange_transactions
+        WHERE type = 'buy'
+          AND (? IS NULL OR timestamp >= ?)
+          AND (? IS NULL OR timestamp <= ?)
+        "#
+    )
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_one(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let buy_count: i64 = row.get("buy_count");
+    if buy_count == 0 {
+        return Err("No buys in the selected period".to_string());
+    }
+    let actual_sats: i64 = row.get("sats");
+    let subtotal_cents: i64 = row.get("subtotal_cents");
+    let fee_cents: i64 = row.get("fee_cents");
+    let first_buy: DateTime<Utc> = row.get("first_buy");
+    let last_buy: DateTime<Utc> = row.get("last_buy");
+
+    let total_contributions_cents = subtotal_cents + fee_cents;
+    let fee_rate = if subtotal_cents > 0 {
+        fee_cents as f64 / subtotal_cents as f64
+    } else {
+        0.0
+    };
+
+    let start = start_date.unwrap_or(first_buy).date_naive();
+    let end = end_date.unwrap_or(last_buy).date_naive();
+
+    let lookback_start = start
+        .checked_sub_days(Days::new(moving_average_days as u64))
+        .unwrap_or(start);
+    let closes = load_daily_closes(pool.inner(), Some(lookback_start), Some(end)).await?;
+    if closes.range(start..=end).next().is_none() {
+        return Err(format!("No stored prices between {} and {}", start, end));
+    }
+
+    // Lump sum on the first priced day of the period
+    let mut lump_sum = Simulation::new("lump_sum", fee_rate);
+    let (first_priced_day, first_price) = closes.range(start..=end).next().unwrap();
+    if *first_priced_day != start {
+        lump_sum.unpriced_days += 1;
+    }
+    lump_sum.buy(total_contributions_cents, *first_price);
+
+    // Fixed weekly and monthly schedules over the same period
+    let weekly_days: Vec<NaiveDate> = start
+        .iter_days()
+        .step_by(7)
+        .take_while(|day| *day <= end)
+        .collect();
+    let mut monthly_days: Vec<NaiveDate> = Vec::new();
+    let mut month = 0;
+    while let Some(day) = start.checked_add_months(Months::new(month)) {
+        if day > end {
+            break;
+        }
+        monthly_days.push(day);
+        month += 1;
+    }
+
+    let weekly_schedule: Vec<(NaiveDate, i64)> = weekly_days
+        .iter()
+        .copied()
+        .zip(split_evenly(total_contributions_cents, weekly_days.len()))
+        .collect();
+    let monthly_schedule: Vec<(NaiveDate, i64)> = monthly_days
+        .iter()
+        .copied()
+        .zip(split_evenly(total_contributions_cents, monthly_days.len()))
+        .collect();
+
+    let mut weekly_dca = Simulation::new("weekly_dca", fee_rate);
+    simulate_schedule(&mut weekly_dca, &weekly_schedule, &closes, end);
+    let mut monthly_dca = Simulation::new("monthly_dca", fee_rate);
+    simulate_schedule(&mut monthly_dca, &monthly_schedule, &closes, end);
+
+    // Buy the dip: the weekly amounts accrue as cash, deployed when the close is below its average
+    let mut buy_the_dip = Simulation::new("buy_the_dip", fee_rate);
+    let mut cash_cents = 0;
+    let mut contributions = weekly_schedule.iter().peekable();
+    for day in start.iter_days().take_while(|day| *day <= end) {
+        while let Some((_, cents)) = contributions.next_if(|(contribution_day, _)| *contribution_day <= day) {
+            cash_cents += cents;
+        }
+        let Some(price_cents) = closes.get(&day) else {
+            continue;
+        };
+        let window_start = day
+            .checked_sub_days(Days::new(moving_average_days as u64 - 1))
+            .unwrap_or(day);
+        let window: Vec<i64> = closes.range(window_start..=day).map(|(_, close)| *close).collect();
+        if window.len() < moving_average_days as usize {
+            continue;
+        }
+        let moving_average = window.iter().sum::<i64>() as f64 / window.len() as f64;
+        if (*price_cents as f64) < moving_average {
+            buy_the_dip.buy(cash_cents, *price_cents);
+            cash_cents = 0;
+        }
+    }
+
+    let actual = StrategyResult {
+        strategy: "actual".to_string(),
+        fiat_invested_cents: total_contributions_cents,
+        fiat_uninvested_cents: 0,
+        sats_acquired: actual_sats,
+        purchase_count: buy_count,
+        unpriced_days: 0,
+        avg_price_cents: if actual_sats > 0 {
+            Some((total_contributions_cents as f64 * 100_000_000.0 / actual_sats as f64).round() as i64)
+        } else {
+            None
+        },
+        sats_vs_actual_percent: 0.0,
+    };
+
+    let comparison = StrategyComparison {
+        start_date: start,
+        end_date: end,
+        total_contributions_cents,
+        fee_rate_percent: fee_rate * 100.0,
+        dip_moving_average_days: moving_average_days,
+        strategies: vec![
+            actual,
+            lump_sum.result(total_contributions_cents, actual_sats),
+            weekly_dca.result(total_contributions_cents, actual_sats),
+            monthly_dca.result(total_contributions_cents, actual_sats),
+            buy_the_dip.result(total_contributions_cents, actual_sats),
+        ],
+    };
+
+    println!(
+        "Simulated {} strategies from {} to {}",
+        comparison.strategies.len(),
+        start,
+        end
+    );
+    Ok(comparison)
+}
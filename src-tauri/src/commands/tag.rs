@@ -0,0 +1,414 @@
+use crate::models::tag::{
+    BulkTagRequest, BulkTagResult, CreateTagRequest, Tag, TagWithUsage, UpdateTagRequest,
+};
+use chrono::Utc;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteExecutor, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+use uuid::Uuid;
+
+/// Unified events record types that can carry tags, with the table each one lives in.
+const TAGGABLE_TABLES: [(&str, &str); 5] = [
+    ("exchange_transaction", "exchange_transactions"),
+    ("onchain_fee", "onchain_fees"),
+    ("balance_adjustment", "balance_adjustments"),
+    ("lightning_payment", "lightning_payments"),
+    ("spending", "spending_events"),
+];
+
+fn table_for_record_type(record_type: &str) -> Result<&'static str, String> {
+    TAGGABLE_TABLES
+        .iter()
+        .find(|(r, _)| *r == record_type)
+        .map(|(_, table)| *table)
+        .ok_or_else(|| format!("Invalid record type: {}", record_type))
+}
+
+/// Serializes a tag filter for binding into `tag_filter_sql`. An empty list means no filter.
+pub(crate) fn tag_filter_json(tag_ids: Option<&[String]>) -> Option<String> {
+    tag_ids
+        .filter(|ids| !ids.is_empty())
+        .map(|ids| serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string()))
+}
+
+/// SQL condition keeping rows of `table` tagged with any of the filtered tags.
+///
+/// Expects the value from `tag_filter_json` to be bound twice.
+pub(crate) fn tag_filter_sql(table: &str, record_type_expr: &str) -> String {
+    format!(
+        "(? IS NULL OR EXISTS (
+            SELECT 1 FROM event_tags et
+            WHERE et.event_type = {record_type_expr} AND et.event_id = {table}.id
+            AND et.tag_id IN (SELECT value FROM json_each(?))
+        ))"
+    )
+}
+
+/// Loads the tags of the given events, keyed by (record_type, id).
+pub(crate) async fn fetch_event_tags(
+    pool: &SqlitePool,
+    event_ids: &[String],
+) -> Result<HashMap<(String, String), Vec<Tag>>, String> {
+    let mut tags_by_event: HashMap<(String, String), Vec<Tag>> = HashMap::new();
+    if event_ids.is_empty() {
+        return Ok(tags_by_event);
+    }
+
+    let ids_json = serde_json::to_string(event_ids).map_err(|e| format!("Serialization error: {}", e))?;
+
+    let rows = sqlx::query(
+        "SELECT et.event_type, et.event_id, t.id, t.name, t.color, t.created_at
+        FROM event_tags et
+        JOIN tags t ON t.id = et.tag_id
+        WHERE et.event_id IN (SELECT value FROM json_each(?))
+        ORDER BY t.name ASC"
+    )
+    .bind(ids_json)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    for row in &rows {
+        tags_by_event
+            .entry((row.get("event_type"), row.get("event_id")))
+            .or_default()
+            .push(tag_from_row(row));
+    }
+
+    Ok(tags_by_event)
+}
+
+fn tag_from_row(row: &SqliteRow) -> Tag {
+    Tag {
+        id: row.get("id"),
+        name: row.get("name"),
+        color: row.get("color"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn normalize_tag_name(name: &str) -> Result<String, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    Ok(name)
+}
+
+async fn fetch_tag(pool: &SqlitePool, id: &str) -> Result<Tag, String> {
+    let row = sqlx::query("SELECT id, name, color, created_at FROM tags WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Tag not found".to_string())?;
+
+    Ok(tag_from_row(&row))
+}
+
+/// Returns the tag with this name, creating it first if needed. Names match case-insensitively.
+pub(crate) async fn find_or_create_tag(pool: &SqlitePool, name: &str) -> Result<Tag, String> {
+    let name = normalize_tag_name(name)?;
+
+    sqlx::query("INSERT OR IGNORE INTO tags (id, name, created_at) VALUES (?, ?, ?)")
+        .bind(Uuid::new_v4().to_string())
+        .bind(&name)
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let row = sqlx::query("SELECT id, name, color, created_at FROM tags WHERE name = ?")
+        .bind(&name)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(tag_from_row(&row))
+}
+
+pub(crate) async fn assign_tag<'e>(
+    executor: impl SqliteExecutor<'e>,
+    tag_id: &str,
+    record_type: &str,
+    event_id: &str,
+) -> Result<bool, String> {
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO event_tags (tag_id, event_type, event_id, created_at) VALUES (?, ?, ?, ?)"
+    )
+    .bind(tag_id)
+    .bind(record_type)
+    .bind(event_id)
+    .bind(Utc::now())
+    .execute(executor)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn create_tag(
+    pool: State<'_, SqlitePool>,
+    request: CreateTagRequest,
+) -> Result<Tag, String> {
+    let tag = Tag {
+        id: Uuid::new_v4().to_string(),
+        name: normalize_tag_name(&request.name)?,
+        color: request.color,
+        created_at: Utc::now(),
+    };
+
+    sqlx::query("INSERT INTO tags (id, name, color, created_at) VALUES (?, ?, ?, ?)")
+        .bind(&tag.id)
+        .bind(&tag.name)
+        .bind(&tag.color)
+        .bind(tag.created_at)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                format!("A tag named '{}' already exists", tag.name)
+            } else {
+                format!("Database error: {}", e)
+            }
+        })?;
+
+    println!("Created tag: {:?}", tag);
+    Ok(tag)
+}
+
+#[tauri::command]
+pub async fn get_tags(pool: State<'_, SqlitePool>) -> Result<Vec<TagWithUsage>, String> {
+    let rows = sqlx::query(
+        "SELECT t.id, t.name, t.color, t.created_at, COUNT(et.event_id) as event_count
+        FROM tags t
+        LEFT JOIN event_tags et ON et.tag_id = t.id
+        GROUP BY t.id
+        ORDER BY t.name ASC"
+    )
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let tags: Vec<TagWithUsage> = rows
+        .iter()
+        .map(|row| TagWithUsage {
+            tag: tag_from_row(row),
+            event_count: row.get("event_count"),
+        })
+        .collect();
+
+    println!("Retrieved {} tags", tags.len());
+    Ok(tags)
+}
+
+/// Renames and recolors a tag. Every tagged event follows since assignments reference the tag id.
+#[tauri::command]
+pub async fn update_tag(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    request: UpdateTagRequest,
+) -> Result<Tag, String> {
+    let name = normalize_tag_name(&request.name)?;
+
+    let result = sqlx::query("UPDATE tags SET name = ?, color = ? WHERE id = ?")
+        .bind(&name)
+        .bind(&request.color)
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("UNIQUE constraint failed") {
+                format!("A tag named '{}' already exists, merge the tags instead", name)
+            } else {
+                format!("Database error: {}", e)
+            }
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err("Tag not found".to_string());
+    }
+
+    let updated_tag = fetch_tag(pool.inner(), &id).await?;
+
+    println!("Updated tag: {:?}", updated_tag);
+    Ok(updated_tag)
+}
+
+/// Moves every assignment of the source tags onto the target tag and deletes the source tags.
+#[tauri::command]
+pub async fn merge_tags(
+    pool: State<'_, SqlitePool>,
+    source_tag_ids: Vec<String>,
+    target_tag_id: String,
+) -> Result<TagWithUsage, String> {
+    fetch_tag(pool.inner(), &target_tag_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    for source_tag_id in source_tag_ids.iter().filter(|id| **id != target_tag_id) {
+        sqlx::query(
+            "INSERT OR IGNORE INTO event_tags (tag_id, event_type, event_id, created_at)
+            SELECT ?, event_type, event_id, created_at FROM event_tags WHERE tag_id = ?"
+        )
+        .bind(&target_tag_id)
+        .bind(source_tag_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        // Remaining assignments of the source tag are removed by ON DELETE CASCADE
+        let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(source_tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err(format!("Tag not found: {}", source_tag_id));
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let event_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM event_tags WHERE tag_id = ?")
+        .bind(&target_tag_id)
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let merged = TagWithUsage {
+        tag: fetch_tag(pool.inner(), &target_tag_id).await?,
+        event_count,
+    };
+
+    println!("Merged {} tags into: {:?}", source_tag_ids.len(), merged);
+    Ok(merged)
+}
+
+#[tauri::command]
+pub async fn delete_tag(pool: State<'_, SqlitePool>, id: String) -> Result<(), String> {
+    // Assignments are removed by ON DELETE CASCADE, the events themselves are untouched
+    let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Tag not found".to_string());
+    }
+
+    println!("Deleted tag with id: {}", id);
+    Ok(())
+}
+
+/// Adds every requested tag to every requested event. Existing assignments are left as they are.
+/// Nothing is assigned when any tag or event does not exist.
+#[tauri::command]
+pub async fn assign_tags(
+    pool: State<'_, SqlitePool>,
+    request: BulkTagRequest,
+) -> Result<BulkTagResult, String> {
+    for tag_id in &request.tag_ids {
+        fetch_tag(pool.inner(), tag_id).await?;
+    }
+
+    let mut changed_count = 0;
+    let mut unchanged_count = 0;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    for event in &request.events {
+        let table = table_for_record_type(&event.record_type)?;
+        let exists: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE id = ?", table))
+            .bind(&event.id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        if exists == 0 {
+            return Err(format!("Event not found: {} {}", event.record_type, event.id));
+        }
+
+        for tag_id in &request.tag_ids {
+            if assign_tag(&mut *tx, tag_id, &event.record_type, &event.id).await? {
+                changed_count += 1;
+            } else {
+                unchanged_count += 1;
+            }
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let result = BulkTagResult {
+        changed_count,
+        unchanged_count,
+    };
+
+    println!("Assigned tags: {:?}", result);
+    Ok(result)
+}
+
+/// Removes every requested tag from every requested event. Nothing is removed when any event has
+/// an unknown record type.
+#[tauri::command]
+pub async fn unassign_tags(
+    pool: State<'_, SqlitePool>,
+    request: BulkTagRequest,
+) -> Result<BulkTagResult, String> {
+    let mut changed_count = 0;
+    let mut unchanged_count = 0;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    for event in &request.events {
+        table_for_record_type(&event.record_type)?;
+
+        for tag_id in &request.tag_ids {
+            let result = sqlx::query(
+                "DELETE FROM event_tags WHERE tag_id = ? AND event_type = ? AND event_id = ?"
+            )
+            .bind(tag_id)
+            .bind(&event.record_type)
+            .bind(&event.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+            if result.rows_affected() > 0 {
+                changed_count += 1;
+            } else {
+                unchanged_count += 1;
+            }
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let result = BulkTagResult {
+        changed_count,
+        unchanged_count,
+    };
+
+    println!("Unassigned tags: {:?}", result);
+    Ok(result)
+}
@@ -0,0 +1,205 @@
+use crate::commands::activity_tool::week_start_date;
+use crate::commands::settings::{load_settings, parse_week_start};
+use crate::models::time_buckets::{BucketSize, TimeBucket};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use tauri::State;
+
+/// Parses an IANA timezone name such as "Europe/Berlin". `None` means UTC.
+pub(crate) fn parse_timezone(timezone: Option<&str>) -> Result<Tz, String> {
+    match timezone {
+        None => Ok(Tz::UTC),
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| format!("Unknown timezone: {}", name)),
+    }
+}
+
+/// Local midnight of `date` in UTC. Zones that skip midnight for DST start at the first valid instant.
+pub(crate) fn local_midnight_utc(tz: &Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    match tz.from_local_datetime(&midnight).earliest() {
+        Some(local) => local.with_timezone(&Utc),
+        None => tz
+            .from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&midnight)),
+    }
+}
+
+fn bucket_start(date: NaiveDate, size: BucketSize, week_start: Weekday) -> NaiveDate {
+    match size {
+        BucketSize::Daily => date,
+        BucketSize::Weekly => week_start_date(date, week_start),
+        BucketSize::Monthly => date.with_day(1).unwrap(),
+        BucketSize::Yearly => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+    }
+}
+
+fn next_bucket(start: NaiveDate, size: BucketSize) -> NaiveDate {
+    match size {
+        BucketSize::Daily => start + Days::new(1),
+        BucketSize::Weekly => start + Days::new(7),
+        BucketSize::Monthly => start + Months::new(1),
+        BucketSize::Yearly => start + Months::new(12),
+    }
+}
+
+fn bucket_label(start: NaiveDate, size: BucketSize) -> String {
+    match size {
+        BucketSize::Daily => start.format("%Y-%m-%d").to_string(),
+        // Named after the ISO week holding most of its days, whatever day it starts on
+        BucketSize::Weekly => (start + Days::new(3)).format("%G-W%V").to_string(),
+        BucketSize::Monthly => start.format("%Y-%m").to_string(),
+        BucketSize::Yearly => start.format("%Y").to_string(),
+    }
+}
+
+fn empty_bucket(tz: &Tz, start: NaiveDate, size: BucketSize) -> TimeBucket {
+    TimeBucket {
+        label: bucket_label(start, size),
+        bucket_start: local_midnight_utc(tz, start),
+        sats_bought: 0,
+        priced_sats_bought: 0,
+        sats_sold: 0,
+        fiat_invested_cents: 0,
+        fiat_extracted_cents: 0,
+        fiat_fees_cents: 0,
+        onchain_fees_sats: 0,
+        sats_spent: 0,
+        fiat_spent_cents: 0,
+        lightning_received_sats: 0,
+        lightning_sent_sats: 0,
+        adjustment_sats: 0,
+        event_count: 0,
+        avg_buy_price: None,
+    }
+}
+
+/// Groups events into calendar buckets of the user's timezone. Weekly buckets start on
+/// `week_start`; the timezone and week start default to the settings.
+///
+/// Every bucket between the first and the last event is returned, including empty ones, so the
+/// result can be charted directly.
+#[tauri::command]
+pub async fn get_time_buckets(
+    pool: State<'_, SqlitePool>,
+    bucket_size: BucketSize,
+    timezone: Option<String>,
+    week_start: Option<String>,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+) -> Result<Vec<TimeBucket>, String> {
+    let settings = load_settings(pool.inner()).await?;
+    let tz = parse_timezone(timezone.as_deref().or(settings.timezone.as_deref()))?;
+    let week_start = parse_week_start(week_start.as_deref().unwrap_or(&settings.week_start))?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT timestamp, kind, amount_sats, subtotal_cents, fee_cents FROM (
+            SELECT timestamp, type as kind, amount_sats, subtotal_cents, fee_cents FROM exchange_transactions
+            UNION ALL
+            SELECT timestamp, 'onchain_fee' as kind, amount_sats, NULL, NULL FROM onchain_fees
+            UNION ALL
+            SELECT timestamp, 'adjustment' as kind, amount_sats, NULL, NULL FROM balance_adjustments
+            UNION ALL
+            SELECT timestamp,
+                CASE WHEN direction = 'incoming' THEN 'lightning_received' ELSE 'lightning_sent' END as kind,
+                CASE WHEN direction = 'incoming' THEN (amount_msats - routing_fee_msats) / 1000 ELSE (amount_msats + routing_fee_msats) / 1000 END,
+                NULL, NULL
+            FROM lightning_payments
+            UNION ALL
+            SELECT timestamp, 'spend' as kind, amount_sats, fiat_value_cents, NULL FROM spending_events
+        )
+        WHERE (? IS NULL OR timestamp >= ?) AND (? IS NULL OR timestamp <= ?)
+        "#
+    )
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut buckets: BTreeMap<NaiveDate, TimeBucket> = BTreeMap::new();
+
+    for row in &rows {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let kind: String = row.get("kind");
+        let amount_sats: i64 = row.get("amount_sats");
+        let subtotal_cents: Option<i64> = row.get("subtotal_cents");
+        let fee_cents: Option<i64> = row.get("fee_cents");
+
+        let start = bucket_start(timestamp.with_timezone(&tz).date_naive(), bucket_size, week_start);
+        let bucket = buckets
+            .entry(start)
+            .or_insert_with(|| empty_bucket(&tz, start, bucket_size));
+
+        bucket.event_count += 1;
+        match kind.as_str() {
+            "buy" => {
+                bucket.sats_bought += amount_sats;
+                if let Some(subtotal_cents) = subtotal_cents {
+                    bucket.priced_sats_bought += amount_sats;
+                    bucket.fiat_invested_cents += subtotal_cents;
+                }
+                bucket.fiat_fees_cents += fee_cents.unwrap_or(0);
+            }
+            "sell" => {
+                bucket.sats_sold += amount_sats;
+                bucket.fiat_extracted_cents += subtotal_cents.unwrap_or(0);
+                bucket.fiat_fees_cents += fee_cents.unwrap_or(0);
+            }
+            "onchain_fee" => bucket.onchain_fees_sats += amount_sats,
+            "spend" => {
+                bucket.sats_spent += amount_sats;
+                bucket.fiat_spent_cents += subtotal_cents.unwrap_or(0);
+            }
+            "lightning_received" => bucket.lightning_received_sats += amount_sats,
+            "lightning_sent" => bucket.lightning_sent_sats += amount_sats,
+            "adjustment" => bucket.adjustment_sats += amount_sats,
+            _ => {}
+        }
+    }
+
+    // Fill the gaps so every bucket in the covered span is present
+    if let (Some(first), Some(last)) = (
+        buckets.keys().next().copied(),
+        buckets.keys().next_back().copied(),
+    ) {
+        let mut start = first;
+        while start < last {
+            buckets
+                .entry(start)
+                .or_insert_with(|| empty_bucket(&tz, start, bucket_size));
+            start = next_bucket(start, bucket_size);
+        }
+    }
+
+    let result: Vec<TimeBucket> = buckets
+        .into_values()
+        .map(|mut bucket| {
+            // Buys without a subtotal would pull the average towards zero
+            if bucket.priced_sats_bought > 0 {
+                bucket.avg_buy_price = Some(
+                    (bucket.fiat_invested_cents as f64 / 100.0)
+                        / (bucket.priced_sats_bought as f64 / 100_000_000.0),
+                );
+            }
+            bucket
+        })
+        .collect();
+
+    println!(
+        "Aggregated {} events into {} {:?} buckets ({})",
+        rows.len(),
+        result.len(),
+        bucket_size,
+        tz
+    );
+    Ok(result)
+}
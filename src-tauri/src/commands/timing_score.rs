@@ -0,0 +1,172 @@
+use crate::commands::settings::resolve_timezone;
+use crate::commands::unified_events::UNIFIED_EVENTS_SQL;
+use crate::models::timing_score::{BuyTimingScore, TimingScoreGroup, TimingScoreReport};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use tauri::State;
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+#[derive(Default)]
+struct TimingTotals {
+    buy_count: i64,
+    scored_count: i64,
+    score_sum: f64,
+    close_count: i64,
+    vs_close_sum: f64,
+}
+
+impl TimingTotals {
+    fn add(&mut self, buy: &BuyTimingScore) {
+        self.buy_count += 1;
+        if let Some(score) = buy.timing_score {
+            self.scored_count += 1;
+            self.score_sum += score;
+        }
+        if let Some(vs_close) = buy.vs_close_percent {
+            self.close_count += 1;
+            self.vs_close_sum += vs_close;
+        }
+    }
+
+    fn into_group(self, group: String) -> TimingScoreGroup {
+        TimingScoreGroup {
+            group,
+            buy_count: self.buy_count,
+            scored_count: self.scored_count,
+            avg_timing_score: if self.scored_count > 0 {
+                Some(self.score_sum / self.scored_count as f64)
+            } else {
+                None
+            },
+            avg_vs_close_percent: if self.close_count > 0 {
+                Some(self.vs_close_sum / self.close_count as f64)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Where the paid price fell within the day's range: 100 at the low, 0 at the high. Prices
+/// outside the range (spread, or a stale range) are clamped.
+fn timing_score(paid_price_cents: i64, low_cents: i64, high_cents: i64) -> Option<f64> {
+    if high_cents < low_cents {
+        return None;
+    }
+    if high_cents == low_cents {
+        return Some(50.0);
+    }
+    let score = (high_cents - paid_price_cents) as f64 / (high_cents - low_cents) as f64 * 100.0;
+    Some(score.clamp(0.0, 100.0))
+}
+
+/// Scores every buy against the stored low, high and close of its UTC day and aggregates the
+/// scores by local weekday, local hour and provider. Complements the weekday counts of
+/// `calculate_best_stacking_day` by showing whether those days were actually cheaper.
+#[tauri::command]
+pub async fn get_purchase_timing_scores(
+    pool: State<'_, SqlitePool>,
+    timezone: Option<String>,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+) -> Result<TimingScoreReport, String> {
+    let tz = resolve_timezone(pool.inner(), timezone.as_deref()).await?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT events.id, events.timestamp, events.provider, events.amount_sats, events.subtotal_cents,
+            prices.low_cents, prices.high_cents, prices.close_cents
+        FROM ({}) AS events
+        LEFT JOIN price_history prices ON prices.date = date(events.timestamp)
+        WHERE events.transaction_type = 'buy' AND events.subtotal_cents > 0 AND events.amount_sats > 0
+            AND (? IS NULL OR events.timestamp >= ?) AND (? IS NULL OR events.timestamp <= ?)
+        ORDER BY events.timestamp ASC",
+        UNIFIED_EVENTS_SQL
+    ))
+    .bind(start_date)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(end_date)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut buys = Vec::new();
+    for row in &rows {
+        let timestamp: DateTime<Utc> = row.get("timestamp");
+        let amount_sats: i64 = row.get("amount_sats");
+        let subtotal_cents: i64 = row.get("subtotal_cents");
+        let low_cents: Option<i64> = row.get("low_cents");
+        let high_cents: Option<i64> = row.get("high_cents");
+        let close_cents: Option<i64> = row.get("close_cents");
+
+        let paid_price_cents = (subtotal_cents as f64 * 100_000_000.0 / amount_sats as f64).round() as i64;
+        let local = timestamp.with_timezone(&tz);
+
+        buys.push(BuyTimingScore {
+            id: row.get("id"),
+            timestamp,
+            provider: row.get("provider"),
+            weekday: WEEKDAYS[local.weekday().num_days_from_monday() as usize].to_string(),
+            hour: local.hour(),
+            paid_price_cents,
+            low_cents,
+            high_cents,
+            close_cents,
+            timing_score: low_cents
+                .zip(high_cents)
+                .and_then(|(low, high)| timing_score(paid_price_cents, low, high)),
+            vs_close_percent: close_cents
+                .filter(|close| *close > 0)
+                .map(|close| (paid_price_cents - close) as f64 / close as f64 * 100.0),
+        });
+    }
+
+    let mut overall = TimingTotals::default();
+    let mut weekday_totals: BTreeMap<u32, TimingTotals> = BTreeMap::new();
+    let mut hour_totals: BTreeMap<u32, TimingTotals> = BTreeMap::new();
+    let mut provider_totals: BTreeMap<String, TimingTotals> = BTreeMap::new();
+    for buy in &buys {
+        let local = buy.timestamp.with_timezone(&tz);
+        overall.add(buy);
+        weekday_totals
+            .entry(local.weekday().num_days_from_monday())
+            .or_default()
+            .add(buy);
+        hour_totals.entry(buy.hour).or_default().add(buy);
+        provider_totals.entry(buy.provider.clone()).or_default().add(buy);
+    }
+
+    let report = TimingScoreReport {
+        timezone: tz.name().to_string(),
+        overall: overall.into_group("all".to_string()),
+        by_weekday: weekday_totals
+            .into_iter()
+            .map(|(weekday, totals)| totals.into_group(WEEKDAYS[weekday as usize].to_string()))
+            .collect(),
+        by_hour: hour_totals
+            .into_iter()
+            .map(|(hour, totals)| totals.into_group(format!("{:02}", hour)))
+            .collect(),
+        by_provider: provider_totals
+            .into_iter()
+            .map(|(provider, totals)| totals.into_group(provider))
+            .collect(),
+        buys,
+    };
+
+    println!(
+        "Scored {} of {} buys against daily price ranges",
+        report.overall.scored_count, report.overall.buy_count
+    );
+    Ok(report)
+}
@@ -1,30 +1,20 @@
-use crate::models::unified_events::{UnifiedEvent, PaginatedUnifiedEvents};
+use crate::commands::pagination::{
+    bind_cursor, decode_cursor, encode_cursor, keyset_sql, validate_page_size,
+};
+use crate::commands::tag::{fetch_event_tags, tag_filter_json, tag_filter_sql};
+use crate::models::tag::Tag;
+use crate::models::unified_events::{
+    EventSearchResult, KeysetUnifiedEvents, PaginatedUnifiedEvents, UnifiedEvent,
+    UnifiedEventsFilter,
+};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteRow};
 use sqlx::{Row, SqlitePool};
 use tauri::State;
 
-#[tauri::command]
-pub async fn get_unified_events(
-    pool: State<'_, SqlitePool>,
-    page: u32,
-    page_size: u32,
-) -> Result<PaginatedUnifiedEvents, String> {
-    let offset = page * page_size;
-
-    // Get total count
-    let total_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM (
-            SELECT id FROM exchange_transactions
-            UNION ALL
-            SELECT id FROM onchain_fees
-        )"
-    )
-    .fetch_one(pool.inner())
-    .await
-    .map_err(|e| format!("Database error: {}", e))?;
-
-    // Get paginated results
-    let rows = sqlx::query(
-        "SELECT 
+/// Every event table projected onto the shared unified events columns.
+pub(crate) const UNIFIED_EVENTS_SQL: &str = "
+        SELECT 
             id,
             'exchange_transaction' as record_type,
             amount_sats,
@@ -33,8 +23,21 @@ pub async fn get_unified_events(
             memo,
             timestamp,
             created_at,
+            account_id,
+            CASE
+                WHEN provider_id IS NULL THEN 'manual'
+                WHEN provider_id LIKE 'coinbase%' THEN 'coinbase'
+                WHEN provider_id LIKE 'river%' THEN 'river'
+                WHEN provider_id LIKE 'stv1-%' THEN 'sat_tracker_v1'
+                ELSE 'manual'
+            END as provider,
             provider_id,
             NULL as tx_hash,
+            NULL as amount_msats,
+            NULL as routing_fee_msats,
+            NULL as payment_hash,
+            NULL as merchant,
+            NULL as category,
             type as transaction_type
         FROM exchange_transactions
         
@@ -49,37 +52,265 @@ pub async fn get_unified_events(
             memo,
             timestamp,
             created_at,
+            account_id,
+            'manual' as provider,
             NULL as provider_id,
             tx_hash,
+            NULL as amount_msats,
+            NULL as routing_fee_msats,
+            NULL as payment_hash,
+            NULL as merchant,
+            NULL as category,
             'fee' as transaction_type
         FROM onchain_fees
         
-        ORDER BY timestamp DESC
-        LIMIT ? OFFSET ?"
+        UNION ALL
+        
+        SELECT 
+            id,
+            'balance_adjustment' as record_type,
+            amount_sats,
+            NULL as subtotal_cents,
+            NULL as fee_cents,
+            memo,
+            timestamp,
+            created_at,
+            account_id,
+            'manual' as provider,
+            NULL as provider_id,
+            NULL as tx_hash,
+            NULL as amount_msats,
+            NULL as routing_fee_msats,
+            NULL as payment_hash,
+            NULL as merchant,
+            NULL as category,
+            'adjustment' as transaction_type
+        FROM balance_adjustments
+        
+        UNION ALL
+        
+        SELECT 
+            id,
+            'lightning_payment' as record_type,
+            amount_msats / 1000 as amount_sats,
+            NULL as subtotal_cents,
+            NULL as fee_cents,
+            memo,
+            timestamp,
+            created_at,
+            account_id,
+            CASE
+                WHEN provider_id LIKE 'lnd%' THEN 'lnd'
+                WHEN provider_id LIKE 'cln%' THEN 'cln'
+                WHEN provider_id LIKE 'phoenix%' THEN 'phoenix'
+                ELSE 'manual'
+            END as provider,
+            provider_id,
+            NULL as tx_hash,
+            amount_msats,
+            routing_fee_msats,
+            payment_hash,
+            NULL as merchant,
+            NULL as category,
+            'lightning_' || direction as transaction_type
+        FROM lightning_payments
+        
+        UNION ALL
+        
+        SELECT 
+            id,
+            'spending' as record_type,
+            amount_sats,
+            fiat_value_cents as subtotal_cents,
+            NULL as fee_cents,
+            memo,
+            timestamp,
+            created_at,
+            account_id,
+            'manual' as provider,
+            NULL as provider_id,
+            NULL as tx_hash,
+            NULL as amount_msats,
+            NULL as routing_fee_msats,
+            NULL as payment_hash,
+            merchant,
+            category,
+            'spend' as transaction_type
+        FROM spending_events
+";
+
+/// Conditions applied to `UNIFIED_EVENTS_SQL` aliased as `events`, bound by `bind_filter`.
+const FILTER_SQL: &str = "
+        (? IS NULL OR events.timestamp >= ?)
+        AND (? IS NULL OR events.timestamp <= ?)
+        AND (? IS NULL OR events.transaction_type IN (SELECT value FROM json_each(?)))
+        AND (? IS NULL OR events.provider = ?)
+        AND (? IS NULL OR events.account_id = ?)
+        AND (? IS NULL OR events.amount_sats >= ?)
+        AND (? IS NULL OR events.amount_sats <= ?)
+        AND (? IS NULL OR events.subtotal_cents >= ?)
+        AND (? IS NULL OR events.subtotal_cents <= ?)
+        AND (? IS NULL OR instr(lower(events.memo), lower(?)) > 0)";
+
+/// Columns `sort_by` may name. Only these are ever interpolated into the query.
+const SORTABLE_COLUMNS: [&str; 13] = [
+    "timestamp",
+    "created_at",
+    "record_type",
+    "transaction_type",
+    "amount_sats",
+    "subtotal_cents",
+    "fee_cents",
+    "memo",
+    "provider",
+    "account_id",
+    "tx_hash",
+    "merchant",
+    "category",
+];
+
+fn bind_filter<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    filter: &'q UnifiedEventsFilter,
+    transaction_types: &'q Option<String>,
+    tag_filter: &'q Option<String>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(filter.start_date)
+        .bind(filter.start_date)
+        .bind(filter.end_date)
+        .bind(filter.end_date)
+        .bind(transaction_types)
+        .bind(transaction_types)
+        .bind(&filter.provider)
+        .bind(&filter.provider)
+        .bind(&filter.account_id)
+        .bind(&filter.account_id)
+        .bind(filter.min_amount_sats)
+        .bind(filter.min_amount_sats)
+        .bind(filter.max_amount_sats)
+        .bind(filter.max_amount_sats)
+        .bind(filter.min_fiat_cents)
+        .bind(filter.min_fiat_cents)
+        .bind(filter.max_fiat_cents)
+        .bind(filter.max_fiat_cents)
+        .bind(&filter.memo_contains)
+        .bind(&filter.memo_contains)
+        .bind(tag_filter)
+        .bind(tag_filter)
+}
+
+/// JSON lists bound by `bind_filter` for the transaction type and tag filters.
+fn filter_json(filter: &UnifiedEventsFilter) -> (Option<String>, Option<String>) {
+    let transaction_types = filter
+        .transaction_types
+        .as_ref()
+        .filter(|types| !types.is_empty())
+        .map(|types| serde_json::to_string(types).unwrap_or_else(|_| "[]".to_string()));
+
+    (transaction_types, tag_filter_json(filter.tag_ids.as_deref()))
+}
+
+fn where_sql() -> String {
+    format!(
+        "{} AND {}",
+        FILTER_SQL,
+        tag_filter_sql("events", "events.record_type")
     )
-    .bind(page_size as i64)
-    .bind(offset as i64)
-    .fetch_all(pool.inner())
-    .await
-    .map_err(|e| format!("Database error: {}", e))?;
+}
+
+fn order_by_sql(filter: &UnifiedEventsFilter) -> Result<String, String> {
+    let column = match filter.sort_by.as_deref() {
+        None => "timestamp",
+        Some(column) => SORTABLE_COLUMNS
+            .iter()
+            .find(|c| **c == column)
+            .ok_or_else(|| format!("Cannot sort by: {}", column))?,
+    };
+
+    let direction = match filter.sort_direction.as_deref() {
+        None | Some("desc") => "DESC",
+        Some("asc") => "ASC",
+        Some(other) => return Err(format!("Invalid sort direction: {}. Use 'asc' or 'desc'", other)),
+    };
+
+    // id keeps the order stable between pages when the sort column has ties
+    Ok(format!("events.{} {}, events.id {}", column, direction, direction))
+}
 
-    let mut events = Vec::new();
-    for row in rows {
-        let event = UnifiedEvent {
-            id: row.get("id"),
-            record_type: row.get("record_type"),
-            amount_sats: row.get("amount_sats"),
-            memo: row.get("memo"),
-            timestamp: row.get("timestamp"),
-            created_at: row.get("created_at"),
-            subtotal_cents: row.get("subtotal_cents"),
-            fee_cents: row.get("fee_cents"),
-            provider_id: row.get("provider_id"),
-            transaction_type: row.get("transaction_type"),
-            tx_hash: row.get("tx_hash"),
-        };
-        events.push(event);
+fn unified_event_from_row(row: &SqliteRow, tags: Vec<Tag>) -> UnifiedEvent {
+    UnifiedEvent {
+        id: row.get("id"),
+        record_type: row.get("record_type"),
+        amount_sats: row.get("amount_sats"),
+        memo: row.get("memo"),
+        timestamp: row.get("timestamp"),
+        created_at: row.get("created_at"),
+        account_id: row.get("account_id"),
+        provider: row.get("provider"),
+        subtotal_cents: row.get("subtotal_cents"),
+        fee_cents: row.get("fee_cents"),
+        provider_id: row.get("provider_id"),
+        transaction_type: row.get("transaction_type"),
+        tx_hash: row.get("tx_hash"),
+        amount_msats: row.get("amount_msats"),
+        routing_fee_msats: row.get("routing_fee_msats"),
+        payment_hash: row.get("payment_hash"),
+        merchant: row.get("merchant"),
+        category: row.get("category"),
+        tags,
     }
+}
+
+#[tauri::command]
+pub async fn get_unified_events(
+    pool: State<'_, SqlitePool>,
+    page: u32,
+    page_size: u32,
+    filter: Option<UnifiedEventsFilter>,
+) -> Result<PaginatedUnifiedEvents, String> {
+    let offset = page * page_size;
+    let filter = filter.unwrap_or_default();
+    let order_by = order_by_sql(&filter)?;
+    let (transaction_types, tag_filter) = filter_json(&filter);
+    let where_clause = where_sql();
+
+    // Get total count
+    let count_sql = format!(
+        "SELECT COUNT(*) as total_count FROM ({}) AS events WHERE {}",
+        UNIFIED_EVENTS_SQL, where_clause
+    );
+    let total_count: i64 = bind_filter(sqlx::query(&count_sql), &filter, &transaction_types, &tag_filter)
+        .fetch_one(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .get("total_count");
+
+    // Get paginated results
+    let events_sql = format!(
+        "SELECT * FROM ({}) AS events
+        WHERE {}
+        ORDER BY {}
+        LIMIT ? OFFSET ?",
+        UNIFIED_EVENTS_SQL, where_clause, order_by
+    );
+    let rows = bind_filter(sqlx::query(&events_sql), &filter, &transaction_types, &tag_filter)
+        .bind(page_size as i64)
+        .bind(offset as i64)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let event_ids: Vec<String> = rows.iter().map(|row| row.get("id")).collect();
+    let mut tags_by_event = fetch_event_tags(pool.inner(), &event_ids).await?;
+
+    let events: Vec<UnifiedEvent> = rows
+        .iter()
+        .map(|row| {
+            let key = (row.get("record_type"), row.get("id"));
+            unified_event_from_row(row, tags_by_event.remove(&key).unwrap_or_default())
+        })
+        .collect();
 
     let total_pages = ((total_count as f64) / (page_size as f64)).ceil() as u32;
     let has_more = (page + 1) * page_size < total_count as u32;
@@ -102,3 +333,150 @@ pub async fn get_unified_events(
     );
     Ok(result)
 }
+
+/// Cursor-based variant of `get_unified_events`. Keyset pages always run newest first, so
+/// `sort_by` and `sort_direction` must be left unset; all other filters apply.
+#[tauri::command]
+pub async fn get_unified_events_after(
+    pool: State<'_, SqlitePool>,
+    cursor: Option<String>,
+    page_size: u32,
+    filter: Option<UnifiedEventsFilter>,
+    include_count: Option<bool>,
+) -> Result<KeysetUnifiedEvents, String> {
+    let filter = filter.unwrap_or_default();
+    if filter.sort_by.is_some() || filter.sort_direction.is_some() {
+        return Err("Cursor pagination is always ordered by timestamp, newest first".to_string());
+    }
+
+    validate_page_size(page_size)?;
+    let cursor = decode_cursor(cursor.as_deref())?;
+    let (transaction_types, tag_filter) = filter_json(&filter);
+    let where_clause = where_sql();
+
+    let total_count: Option<i64> = if include_count.unwrap_or(false) {
+        let count_sql = format!(
+            "SELECT COUNT(*) as total_count FROM ({}) AS events WHERE {}",
+            UNIFIED_EVENTS_SQL, where_clause
+        );
+        Some(
+            bind_filter(sqlx::query(&count_sql), &filter, &transaction_types, &tag_filter)
+                .fetch_one(pool.inner())
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .get("total_count"),
+        )
+    } else {
+        None
+    };
+
+    // One extra row tells whether another page follows
+    let events_sql = format!(
+        "SELECT * FROM ({}) AS events
+        WHERE {} AND {}
+        ORDER BY events.timestamp DESC, events.id DESC
+        LIMIT ?",
+        UNIFIED_EVENTS_SQL,
+        where_clause,
+        keyset_sql(&cursor, "events.timestamp", "events.id")
+    );
+    let query = bind_filter(sqlx::query(&events_sql), &filter, &transaction_types, &tag_filter);
+    let mut rows = bind_cursor(query, &cursor)
+        .bind(page_size as i64 + 1)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let has_more = rows.len() > page_size as usize;
+    rows.truncate(page_size as usize);
+    let next_cursor = if has_more { rows.last().map(encode_cursor) } else { None };
+
+    let event_ids: Vec<String> = rows.iter().map(|row| row.get("id")).collect();
+    let mut tags_by_event = fetch_event_tags(pool.inner(), &event_ids).await?;
+
+    let events: Vec<UnifiedEvent> = rows
+        .iter()
+        .map(|row| {
+            let key = (row.get("record_type"), row.get("id"));
+            unified_event_from_row(row, tags_by_event.remove(&key).unwrap_or_default())
+        })
+        .collect();
+
+    println!(
+        "Retrieved {} unified events after cursor {:?} (has_more: {})",
+        events.len(),
+        cursor,
+        has_more
+    );
+    Ok(KeysetUnifiedEvents {
+        events,
+        page_size,
+        next_cursor,
+        has_more,
+        total_count,
+    })
+}
+
+/// Turns free text into an FTS5 query: every word must match, as a prefix, in any indexed column.
+/// Words are quoted so FTS5 syntax characters typed by the user are searched literally.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Full-text search over memos, provider ids, transaction and payment hashes and spending labels.
+#[tauri::command]
+pub async fn search_events(
+    pool: State<'_, SqlitePool>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<EventSearchResult>, String> {
+    let match_query = match fts_query(&query) {
+        Some(match_query) => match_query,
+        None => return Ok(Vec::new()),
+    };
+
+    let rows = sqlx::query(&format!(
+        "SELECT events.*,
+            snippet(event_search, -1, '<mark>', '</mark>', '…', 12) as snippet,
+            bm25(event_search) as rank
+        FROM event_search
+        JOIN ({}) AS events
+            ON events.id = event_search.event_id AND events.record_type = event_search.record_type
+        WHERE event_search MATCH ?
+        ORDER BY rank ASC, events.timestamp DESC
+        LIMIT ?",
+        UNIFIED_EVENTS_SQL
+    ))
+    .bind(&match_query)
+    .bind(limit.unwrap_or(50) as i64)
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let event_ids: Vec<String> = rows.iter().map(|row| row.get("id")).collect();
+    let mut tags_by_event = fetch_event_tags(pool.inner(), &event_ids).await?;
+
+    let results: Vec<EventSearchResult> = rows
+        .iter()
+        .map(|row| {
+            let key = (row.get("record_type"), row.get("id"));
+            EventSearchResult {
+                event: unified_event_from_row(row, tags_by_event.remove(&key).unwrap_or_default()),
+                snippet: row.get("snippet"),
+                rank: row.get("rank"),
+            }
+        })
+        .collect();
+
+    println!("Found {} events matching '{}'", results.len(), query);
+    Ok(results)
+}
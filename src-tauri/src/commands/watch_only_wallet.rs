@@ -0,0 +1,1080 @@
+use crate::commands::account::insert_account;
+use crate::commands::overview_tool::calculate_overview_metrics;
+use crate::models::account::{AccountKind, CreateAccountRequest};
+use crate::models::overview::OverviewFilter;
+use crate::models::wallet::{
+    ColdStorageReconciliation, CreateWatchOnlyWalletRequest, WalletActivityImportResult,
+    WalletAddress, WalletBalance, WatchOnlyWallet,
+};
+use bitcoin::bip32::{ChildNumber, Xpub};
+use bitcoin::secp256k1::{Secp256k1, Verification};
+use bitcoin::{Address, Network, NetworkKind};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqliteExecutor, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+use uuid::Uuid;
+
+const DEFAULT_GAP_LIMIT: u32 = 20;
+const MAX_GAP_LIMIT: u32 = 1000;
+
+// ============================================================================
+// DESCRIPTOR PARSING AND ADDRESS DERIVATION
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScriptType {
+    P2pkh,
+    P2shP2wpkh,
+    P2wpkh,
+    P2tr,
+}
+
+impl ScriptType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ScriptType::P2pkh => "p2pkh",
+            ScriptType::P2shP2wpkh => "p2sh-p2wpkh",
+            ScriptType::P2wpkh => "p2wpkh",
+            ScriptType::P2tr => "p2tr",
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "p2pkh" | "pkh" | "legacy" => Ok(ScriptType::P2pkh),
+            "p2sh-p2wpkh" | "sh(wpkh)" | "nested-segwit" => Ok(ScriptType::P2shP2wpkh),
+            "p2wpkh" | "wpkh" | "native-segwit" => Ok(ScriptType::P2wpkh),
+            "p2tr" | "tr" | "taproot" => Ok(ScriptType::P2tr),
+            _ => Err(format!("Unsupported script type: {}", s)),
+        }
+    }
+}
+
+/// A single-key watch-only descriptor: `SCRIPT([origin]xpub/prefix/<receive;change>/*)`
+#[derive(Debug, Clone)]
+struct WalletDescriptor {
+    script_type: ScriptType,
+    origin: Option<String>,
+    xpub: Xpub,
+    path_prefix: Vec<u32>,
+    receive_chain: u32,
+    change_chain: u32,
+}
+
+impl std::fmt::Display for WalletDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut key = String::new();
+        if let Some(origin) = &self.origin {
+            key.push_str(&format!("[{}]", origin));
+        }
+        key.push_str(&self.xpub.to_string());
+        for step in &self.path_prefix {
+            key.push_str(&format!("/{}", step));
+        }
+        key.push_str(&format!("/<{};{}>/*", self.receive_chain, self.change_chain));
+
+        match self.script_type {
+            ScriptType::P2pkh => write!(f, "pkh({})", key),
+            ScriptType::P2shP2wpkh => write!(f, "sh(wpkh({}))", key),
+            ScriptType::P2wpkh => write!(f, "wpkh({})", key),
+            ScriptType::P2tr => write!(f, "tr({})", key),
+        }
+    }
+}
+
+impl WalletDescriptor {
+    fn network(&self) -> Network {
+        match self.xpub.network {
+            NetworkKind::Main => Network::Bitcoin,
+            NetworkKind::Test => Network::Testnet,
+        }
+    }
+
+    fn derive_address<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        chain: u32,
+        index: u32,
+    ) -> Result<String, String> {
+        let chain_step = if chain == 0 {
+            self.receive_chain
+        } else {
+            self.change_chain
+        };
+
+        let path = self
+            .path_prefix
+            .iter()
+            .chain([chain_step, index].iter())
+            .map(|step| ChildNumber::from_normal_idx(*step))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid derivation path: {}", e))?;
+
+        let child = self
+            .xpub
+            .derive_pub(secp, &path)
+            .map_err(|e| format!("Failed to derive key: {}", e))?;
+
+        let network = self.network();
+        let address = match self.script_type {
+            ScriptType::P2pkh => Address::p2pkh(child.to_pub(), network),
+            ScriptType::P2shP2wpkh => Address::p2shwpkh(&child.to_pub(), network),
+            ScriptType::P2wpkh => Address::p2wpkh(&child.to_pub(), network),
+            ScriptType::P2tr => Address::p2tr(secp, child.to_x_only_pub(), None, network),
+        };
+
+        Ok(address.to_string())
+    }
+}
+
+/// Decodes xpub/ypub/zpub (and testnet equivalents), returning the key in plain xpub/tpub form
+/// together with the script type implied by its SLIP-132 version bytes.
+fn decode_extended_public_key(key: &str) -> Result<(Xpub, Option<ScriptType>), String> {
+    let mut data = bitcoin::base58::decode_check(key)
+        .map_err(|e| format!("Invalid extended public key: {}", e))?;
+
+    if data.len() != 78 {
+        return Err("Invalid extended public key length".to_string());
+    }
+
+    let (version, implied_script_type): ([u8; 4], Option<ScriptType>) = match data[0..4] {
+        [0x04, 0x88, 0xB2, 0x1E] => ([0x04, 0x88, 0xB2, 0x1E], None), // xpub
+        [0x04, 0x9D, 0x7C, 0xB2] => ([0x04, 0x88, 0xB2, 0x1E], Some(ScriptType::P2shP2wpkh)), // ypub
+        [0x04, 0xB2, 0x47, 0x46] => ([0x04, 0x88, 0xB2, 0x1E], Some(ScriptType::P2wpkh)), // zpub
+        [0x04, 0x35, 0x87, 0xCF] => ([0x04, 0x35, 0x87, 0xCF], None), // tpub
+        [0x04, 0x4A, 0x52, 0x62] => ([0x04, 0x35, 0x87, 0xCF], Some(ScriptType::P2shP2wpkh)), // upub
+        [0x04, 0x5F, 0x1C, 0xF6] => ([0x04, 0x35, 0x87, 0xCF], Some(ScriptType::P2wpkh)), // vpub
+        [0x02, 0x95, 0xB4, 0x3F] | [0x02, 0xAA, 0x7E, 0xD3] | [0x02, 0x42, 0x89, 0xEF]
+        | [0x02, 0x57, 0x54, 0x83] => {
+            return Err("Multisig extended keys (Ypub/Zpub) are not supported".to_string())
+        }
+        _ => {
+            return Err(
+                "Unrecognized extended key. Private keys are never accepted, use an xpub/ypub/zpub"
+                    .to_string(),
+            )
+        }
+    };
+
+    data[0..4].copy_from_slice(&version);
+    let xpub = Xpub::decode(&data).map_err(|e| format!("Invalid extended public key: {}", e))?;
+
+    Ok((xpub, implied_script_type))
+}
+
+fn parse_unhardened_step(step: &str) -> Result<u32, String> {
+    if step.ends_with('h') || step.ends_with('\'') || step.ends_with('H') {
+        return Err(format!(
+            "Hardened derivation step '{}' cannot be derived from a public key",
+            step
+        ));
+    }
+    step.parse::<u32>()
+        .map_err(|_| format!("Invalid derivation step '{}'", step))
+}
+
+fn parse_wallet_descriptor(
+    input: &str,
+    script_type_override: Option<&str>,
+) -> Result<WalletDescriptor, String> {
+    // Drop the descriptor checksum, we re-normalize the descriptor anyway
+    let input = input.trim().split('#').next().unwrap_or("").trim();
+    if input.is_empty() {
+        return Err("Descriptor cannot be empty".to_string());
+    }
+
+    let wrappers = [
+        ("sh(wpkh(", "))", ScriptType::P2shP2wpkh),
+        ("wpkh(", ")", ScriptType::P2wpkh),
+        ("pkh(", ")", ScriptType::P2pkh),
+        ("tr(", ")", ScriptType::P2tr),
+    ];
+
+    let mut descriptor_script_type = None;
+    let mut key_expression = input;
+    for (prefix, suffix, script_type) in wrappers {
+        if let Some(inner) = input.strip_prefix(prefix) {
+            key_expression = inner
+                .strip_suffix(suffix)
+                .ok_or_else(|| format!("Malformed descriptor: {}", input))?;
+            descriptor_script_type = Some(script_type);
+            break;
+        }
+    }
+
+    if descriptor_script_type.is_none() && input.contains('(') {
+        return Err(
+            "Only single-key pkh(), wpkh(), sh(wpkh()) and tr() descriptors are supported"
+                .to_string(),
+        );
+    }
+
+    let (origin, key_and_path) = match key_expression.strip_prefix('[') {
+        Some(rest) => {
+            let end = rest
+                .find(']')
+                .ok_or_else(|| "Malformed key origin in descriptor".to_string())?;
+            (Some(rest[..end].to_string()), &rest[end + 1..])
+        }
+        None => (None, key_expression),
+    };
+
+    let mut segments = key_and_path.split('/');
+    let key = segments.next().unwrap_or("");
+    let (xpub, implied_script_type) = decode_extended_public_key(key)?;
+
+    let path: Vec<&str> = segments.collect();
+    let (path_prefix, receive_chain, change_chain) = if path.is_empty() {
+        (Vec::new(), 0, 1)
+    } else {
+        if path.last() != Some(&"*") || path.len() < 2 {
+            return Err("Descriptor path must end with /<0;1>/* or /0/*".to_string());
+        }
+
+        let chain = path[path.len() - 2];
+        let (receive_chain, change_chain) = match chain
+            .strip_prefix('<')
+            .and_then(|c| c.strip_suffix('>'))
+        {
+            Some(multipath) => {
+                let parts: Vec<&str> = multipath.split(';').collect();
+                if parts.len() != 2 {
+                    return Err(format!("Unsupported multipath step: {}", chain));
+                }
+                (parse_unhardened_step(parts[0])?, parse_unhardened_step(parts[1])?)
+            }
+            // Wallets export receive and change as separate /0/* and /1/* descriptors
+            None => {
+                let receive = parse_unhardened_step(chain)?;
+                (receive, receive + 1)
+            }
+        };
+
+        let path_prefix = path[..path.len() - 2]
+            .iter()
+            .map(|step| parse_unhardened_step(step))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        (path_prefix, receive_chain, change_chain)
+    };
+
+    let requested_script_type = script_type_override.map(ScriptType::parse).transpose()?;
+
+    let script_type = match (descriptor_script_type, requested_script_type) {
+        (Some(from_descriptor), Some(requested)) if from_descriptor != requested => {
+            return Err(format!(
+                "Descriptor is {} but script type {} was requested",
+                from_descriptor.as_str(),
+                requested.as_str()
+            ))
+        }
+        (Some(from_descriptor), _) => from_descriptor,
+        (None, Some(requested)) => requested,
+        (None, None) => implied_script_type.unwrap_or(ScriptType::P2pkh),
+    };
+
+    Ok(WalletDescriptor {
+        script_type,
+        origin,
+        xpub,
+        path_prefix,
+        receive_chain,
+        change_chain,
+    })
+}
+
+// ============================================================================
+// WALLET STORAGE
+// ============================================================================
+
+fn wallet_from_row(row: &SqliteRow) -> WatchOnlyWallet {
+    WatchOnlyWallet {
+        id: row.get("id"),
+        account_id: row.get("account_id"),
+        label: row.get("label"),
+        descriptor: row.get("descriptor"),
+        script_type: row.get("script_type"),
+        network: row.get("network"),
+        receive_gap_limit: row.get::<i64, _>("receive_gap_limit") as u32,
+        change_gap_limit: row.get::<i64, _>("change_gap_limit") as u32,
+        created_at: row.get("created_at"),
+    }
+}
+
+async fn fetch_wallet<'e>(
+    executor: impl SqliteExecutor<'e>,
+    id: &str,
+) -> Result<WatchOnlyWallet, String> {
+    let row = sqlx::query(
+        "SELECT id, account_id, label, descriptor, script_type, network, receive_gap_limit, change_gap_limit, created_at FROM wallets WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| "Wallet not found".to_string())?;
+
+    Ok(wallet_from_row(&row))
+}
+
+fn validate_gap_limit(gap_limit: u32) -> Result<u32, String> {
+    if gap_limit == 0 || gap_limit > MAX_GAP_LIMIT {
+        return Err(format!("Gap limit must be between 1 and {}", MAX_GAP_LIMIT));
+    }
+    Ok(gap_limit)
+}
+
+/// Derives addresses until each chain has `gap_limit` unused addresses after the last used one.
+/// Returns the number of newly derived addresses. Runs on the caller's transaction.
+async fn extend_wallet_addresses(
+    conn: &mut SqliteConnection,
+    wallet: &WatchOnlyWallet,
+) -> Result<usize, String> {
+    let descriptor = parse_wallet_descriptor(&wallet.descriptor, Some(&wallet.script_type))?;
+    let secp = Secp256k1::verification_only();
+    let mut derived_count = 0;
+
+    for (chain, gap_limit) in [(0u32, wallet.receive_gap_limit), (1u32, wallet.change_gap_limit)] {
+        let row = sqlx::query(
+            "SELECT
+                COALESCE(MAX(derivation_index) + 1, 0) as next_index,
+                COALESCE(MAX(CASE WHEN used = 1 THEN derivation_index END) + 1, 0) as first_after_used
+            FROM wallet_addresses WHERE wallet_id = ? AND chain = ?"
+        )
+        .bind(&wallet.id)
+        .bind(chain as i64)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let next_index: i64 = row.get("next_index");
+        let first_after_used: i64 = row.get("first_after_used");
+        let target = first_after_used + gap_limit as i64;
+
+        for index in next_index..target {
+            let address = descriptor.derive_address(&secp, chain, index as u32)?;
+
+            sqlx::query(
+                "INSERT INTO wallet_addresses (wallet_id, chain, derivation_index, address, used) VALUES (?, ?, ?, ?, 0)"
+            )
+            .bind(&wallet.id)
+            .bind(chain as i64)
+            .bind(index)
+            .bind(&address)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+            derived_count += 1;
+        }
+    }
+
+    Ok(derived_count)
+}
+
+#[tauri::command]
+pub async fn create_watch_only_wallet(
+    pool: State<'_, SqlitePool>,
+    request: CreateWatchOnlyWalletRequest,
+) -> Result<WatchOnlyWallet, String> {
+    let label = request.label.trim().to_string();
+    if label.is_empty() {
+        return Err("Wallet label cannot be empty".to_string());
+    }
+
+    let receive_gap_limit = validate_gap_limit(request.receive_gap_limit.unwrap_or(DEFAULT_GAP_LIMIT))?;
+    let change_gap_limit = validate_gap_limit(request.change_gap_limit.unwrap_or(DEFAULT_GAP_LIMIT))?;
+
+    let descriptor = parse_wallet_descriptor(&request.descriptor, request.script_type.as_deref())?;
+
+    // The account, the wallet and its first addresses are created together or not at all
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let account_id = match request.account_id {
+        Some(account_id) => account_id,
+        None => {
+            insert_account(
+                &mut *tx,
+                CreateAccountRequest {
+                    name: label.clone(),
+                    kind: AccountKind::Wallet,
+                },
+            )
+            .await?
+            .id
+        }
+    };
+
+    let wallet = WatchOnlyWallet {
+        id: Uuid::new_v4().to_string(),
+        account_id,
+        label,
+        descriptor: descriptor.to_string(),
+        script_type: descriptor.script_type.as_str().to_string(),
+        network: descriptor.network().to_string(),
+        receive_gap_limit,
+        change_gap_limit,
+        created_at: Utc::now(),
+    };
+
+    sqlx::query(
+        "INSERT INTO wallets (id, account_id, label, descriptor, script_type, network, receive_gap_limit, change_gap_limit, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(&wallet.id)
+    .bind(&wallet.account_id)
+    .bind(&wallet.label)
+    .bind(&wallet.descriptor)
+    .bind(&wallet.script_type)
+    .bind(&wallet.network)
+    .bind(wallet.receive_gap_limit as i64)
+    .bind(wallet.change_gap_limit as i64)
+    .bind(wallet.created_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            "This wallet has already been added".to_string()
+        } else {
+            format!("Database error: {}", e)
+        }
+    })?;
+
+    let derived_count = extend_wallet_addresses(&mut tx, &wallet).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    println!(
+        "Created watch-only wallet '{}' ({}), derived {} addresses",
+        wallet.label, wallet.script_type, derived_count
+    );
+    Ok(wallet)
+}
+
+#[tauri::command]
+pub async fn get_watch_only_wallets(
+    pool: State<'_, SqlitePool>,
+) -> Result<Vec<WatchOnlyWallet>, String> {
+    let rows = sqlx::query(
+        "SELECT id, account_id, label, descriptor, script_type, network, receive_gap_limit, change_gap_limit, created_at FROM wallets ORDER BY label ASC"
+    )
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let wallets: Vec<WatchOnlyWallet> = rows.iter().map(wallet_from_row).collect();
+
+    println!("Retrieved {} watch-only wallets", wallets.len());
+    Ok(wallets)
+}
+
+#[tauri::command]
+pub async fn update_wallet_gap_limits(
+    pool: State<'_, SqlitePool>,
+    id: String,
+    receive_gap_limit: u32,
+    change_gap_limit: u32,
+) -> Result<WatchOnlyWallet, String> {
+    let receive_gap_limit = validate_gap_limit(receive_gap_limit)?;
+    let change_gap_limit = validate_gap_limit(change_gap_limit)?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let result = sqlx::query(
+        "UPDATE wallets SET receive_gap_limit = ?, change_gap_limit = ? WHERE id = ?"
+    )
+    .bind(receive_gap_limit as i64)
+    .bind(change_gap_limit as i64)
+    .bind(&id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Wallet not found".to_string());
+    }
+
+    // Lowering the gap limit keeps already derived addresses, raising it derives more
+    let wallet = fetch_wallet(&mut *tx, &id).await?;
+    let derived_count = extend_wallet_addresses(&mut tx, &wallet).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    println!(
+        "Updated gap limits for wallet '{}', derived {} new addresses",
+        wallet.label, derived_count
+    );
+    Ok(wallet)
+}
+
+#[tauri::command]
+pub async fn delete_watch_only_wallet(
+    pool: State<'_, SqlitePool>,
+    id: String,
+) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM wallets WHERE id = ?")
+        .bind(&id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Wallet not found".to_string());
+    }
+
+    println!("Deleted watch-only wallet with id: {}", id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_wallet_addresses(
+    pool: State<'_, SqlitePool>,
+    wallet_id: String,
+    chain: Option<u32>,
+) -> Result<Vec<WalletAddress>, String> {
+    let rows = sqlx::query(
+        "SELECT wallet_id, chain, derivation_index, address, used FROM wallet_addresses
+        WHERE wallet_id = ? AND (? IS NULL OR chain = ?)
+        ORDER BY chain ASC, derivation_index ASC"
+    )
+    .bind(&wallet_id)
+    .bind(chain.map(|c| c as i64))
+    .bind(chain.map(|c| c as i64))
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let addresses = rows
+        .iter()
+        .map(|row| WalletAddress {
+            wallet_id: row.get("wallet_id"),
+            chain: row.get::<i64, _>("chain") as u32,
+            derivation_index: row.get::<i64, _>("derivation_index") as u32,
+            address: row.get("address"),
+            used: row.get("used"),
+        })
+        .collect();
+
+    Ok(addresses)
+}
+
+// ============================================================================
+// ON-CHAIN ACTIVITY IMPORT
+// ============================================================================
+
+#[derive(Debug)]
+enum WalletExportFormat {
+    SparrowUtxos,        // Date, Output, Address, Label, Value
+    SparrowTransactions, // Date, Label, Value, Balance, Fee, Txid
+    ElectrumHistory,     // transaction_hash, label, confirmations, value, fiat_value, fee, fiat_fee, timestamp
+    AddressList,         // txid, vout, address, amount_sats, timestamp
+}
+
+#[derive(Debug)]
+struct WalletActivityRow {
+    txid: String,
+    vout: i64,
+    address: Option<String>,
+    amount_sats: i64,
+    fee_sats: Option<i64>,
+    memo: Option<String>,
+    timestamp: Option<DateTime<Utc>>, // None while unconfirmed
+}
+
+/// What storing an imported row did.
+#[derive(Debug, PartialEq)]
+enum ActivityInsert {
+    Inserted,
+    Confirmed, // the row was stored unconfirmed and now has its date
+    Duplicate,
+}
+
+fn column_index(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    headers.iter().position(|header| {
+        let header = header.trim().to_lowercase();
+        names.iter().any(|name| header == *name || header.starts_with(&format!("{} (", name)))
+    })
+}
+
+fn detect_wallet_export_format(headers: &csv::StringRecord) -> Result<WalletExportFormat, String> {
+    let has = |name: &str| column_index(headers, &[name]).is_some();
+
+    if has("output") && has("address") && has("value") {
+        Ok(WalletExportFormat::SparrowUtxos)
+    } else if has("txid") && has("value") && has("balance") {
+        Ok(WalletExportFormat::SparrowTransactions)
+    } else if has("transaction_hash") && has("value") && has("timestamp") {
+        Ok(WalletExportFormat::ElectrumHistory)
+    } else if has("txid") && has("address") && has("amount_sats") {
+        Ok(WalletExportFormat::AddressList)
+    } else {
+        Err("Unrecognized wallet export. Expected a Sparrow or Electrum export".to_string())
+    }
+}
+
+/// Parses a date column. Unconfirmed transactions have no date yet and give `None`.
+fn parse_wallet_timestamp(value: &str) -> Result<Option<DateTime<Utc>>, String> {
+    let value = value.trim();
+
+    if value.is_empty() || value.eq_ignore_ascii_case("unconfirmed") {
+        return Ok(None);
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(dt.with_timezone(&Utc)));
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(Some(dt.and_utc()));
+        }
+    }
+
+    if let Ok(seconds) = value.parse::<i64>() {
+        if let Some(dt) = DateTime::from_timestamp(seconds, 0) {
+            return Ok(Some(dt));
+        }
+    }
+
+    Err(format!("Failed to parse wallet timestamp '{}'", value))
+}
+
+/// Parses a value column. Values with a decimal point are BTC, otherwise sats.
+fn parse_wallet_amount(value: &str) -> Result<i64, String> {
+    let cleaned = value.trim().replace([',', '+'], "");
+    if cleaned.is_empty() {
+        return Ok(0);
+    }
+
+    if cleaned.contains('.') {
+        let btc: f64 = cleaned
+            .parse()
+            .map_err(|e| format!("Failed to parse BTC amount '{}': {}", value, e))?;
+        Ok((btc * 100_000_000.0).round() as i64)
+    } else {
+        cleaned
+            .parse()
+            .map_err(|e| format!("Failed to parse sats amount '{}': {}", value, e))
+    }
+}
+
+fn optional_field(record: &csv::StringRecord, index: Option<usize>) -> Option<String> {
+    index
+        .and_then(|i| record.get(i))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_wallet_export(content: &str) -> Result<(WalletExportFormat, Vec<WalletActivityRow>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .clone();
+    let format = detect_wallet_export_format(&headers)?;
+
+    let date_column = column_index(&headers, &["date", "timestamp"]);
+    let label_column = column_index(&headers, &["label"]);
+    let value_column = column_index(&headers, &["value", "amount_sats"]);
+    let fee_column = column_index(&headers, &["fee"]);
+    let address_column = column_index(&headers, &["address"]);
+    let txid_column = column_index(&headers, &["txid", "transaction_hash"]);
+    let output_column = column_index(&headers, &["output"]);
+    let vout_column = column_index(&headers, &["vout"]);
+
+    let mut rows = Vec::new();
+    for result in reader.records() {
+        let record = result.map_err(|e| format!("Failed to parse CSV record: {}", e))?;
+
+        let (txid, vout) = match format {
+            WalletExportFormat::SparrowUtxos => {
+                let output = optional_field(&record, output_column)
+                    .ok_or_else(|| "Missing output in Sparrow UTXO export".to_string())?;
+                let (txid, vout) = output
+                    .rsplit_once(':')
+                    .ok_or_else(|| format!("Invalid output '{}'", output))?;
+                let vout = vout
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid output '{}'", output))?;
+                (txid.to_string(), vout)
+            }
+            WalletExportFormat::AddressList => {
+                let txid = optional_field(&record, txid_column)
+                    .ok_or_else(|| "Missing txid".to_string())?;
+                let vout = optional_field(&record, vout_column)
+                    .map(|v| v.parse::<i64>().map_err(|_| format!("Invalid vout '{}'", v)))
+                    .transpose()?
+                    .unwrap_or(-1);
+                (txid, vout)
+            }
+            WalletExportFormat::SparrowTransactions | WalletExportFormat::ElectrumHistory => {
+                let txid = optional_field(&record, txid_column)
+                    .ok_or_else(|| "Missing transaction id".to_string())?;
+                (txid, -1)
+            }
+        };
+
+        let amount_sats = parse_wallet_amount(
+            &optional_field(&record, value_column).unwrap_or_default(),
+        )?;
+        let fee_sats = optional_field(&record, fee_column)
+            .map(|fee| parse_wallet_amount(&fee).map(i64::abs))
+            .transpose()?;
+        let timestamp =
+            parse_wallet_timestamp(&optional_field(&record, date_column).unwrap_or_default())?;
+
+        rows.push(WalletActivityRow {
+            txid,
+            vout,
+            address: optional_field(&record, address_column),
+            amount_sats,
+            fee_sats,
+            memo: optional_field(&record, label_column),
+            timestamp,
+        });
+    }
+
+    Ok((format, rows))
+}
+
+/// Per-output exports (Sparrow UTXOs, address lists with a vout) and per-transaction exports
+/// describe the same coins differently, so summing both double counts the balance. A wallet
+/// only ever holds one of the two.
+async fn check_activity_granularity(
+    conn: &mut SqliteConnection,
+    wallet_id: &str,
+    per_output: bool,
+) -> Result<(), String> {
+    let mixed: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM wallet_activity WHERE wallet_id = ? AND (vout >= 0) != ?)"
+    )
+    .bind(wallet_id)
+    .bind(per_output)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    if mixed {
+        let wallet = fetch_wallet(&mut *conn, wallet_id).await?;
+        return Err(format!(
+            "Wallet '{}' already has {} activity. Per-output (UTXO) and per-transaction exports cannot be mixed in one wallet",
+            wallet.label,
+            if per_output { "per-transaction" } else { "per-output" }
+        ));
+    }
+
+    Ok(())
+}
+
+/// A per-output export is the wallet's UTXO set at export time, so stored outputs it no longer
+/// lists have been spent. Deletes them and returns how many were deleted.
+async fn remove_spent_outputs(
+    conn: &mut SqliteConnection,
+    wallet_id: &str,
+    outputs: &HashSet<(String, i64)>,
+) -> Result<usize, String> {
+    let rows = sqlx::query("SELECT id, txid, vout FROM wallet_activity WHERE wallet_id = ? AND vout >= 0")
+        .bind(wallet_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut spent_count = 0;
+    for row in rows {
+        if outputs.contains(&(row.get("txid"), row.get("vout"))) {
+            continue;
+        }
+
+        let id: String = row.get("id");
+        sqlx::query("DELETE FROM wallet_activity WHERE id = ?")
+            .bind(&id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        spent_count += 1;
+    }
+
+    Ok(spent_count)
+}
+
+/// Stores an imported row, or dates an unconfirmed row imported earlier.
+async fn insert_wallet_activity(
+    conn: &mut SqliteConnection,
+    wallet_id: &str,
+    row: &WalletActivityRow,
+) -> Result<ActivityInsert, String> {
+    let existing = sqlx::query(
+        "SELECT id, timestamp FROM wallet_activity WHERE wallet_id = ? AND txid = ? AND vout = ?"
+    )
+    .bind(wallet_id)
+    .bind(&row.txid)
+    .bind(row.vout)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    if let Some(existing) = existing {
+        let stored_timestamp: Option<DateTime<Utc>> = existing.get("timestamp");
+        if stored_timestamp.is_some() || row.timestamp.is_none() {
+            return Ok(ActivityInsert::Duplicate);
+        }
+
+        let id: String = existing.get("id");
+        sqlx::query("UPDATE wallet_activity SET timestamp = ? WHERE id = ?")
+            .bind(row.timestamp)
+            .bind(&id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        return Ok(ActivityInsert::Confirmed);
+    }
+
+    sqlx::query(
+        "INSERT INTO wallet_activity (id, wallet_id, txid, vout, address, amount_sats, fee_sats, memo, timestamp, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(wallet_id)
+    .bind(&row.txid)
+    .bind(row.vout)
+    .bind(&row.address)
+    .bind(row.amount_sats)
+    .bind(row.fee_sats)
+    .bind(&row.memo)
+    .bind(row.timestamp)
+    .bind(Utc::now())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(ActivityInsert::Inserted)
+}
+
+/// Attributes manually recorded on-chain fees to the wallet that paid them.
+async fn match_onchain_fees(
+    conn: &mut SqliteConnection,
+    wallet_id: &str,
+    txid: &str,
+) -> Result<u64, String> {
+    let result = sqlx::query(
+        "UPDATE onchain_fees SET account_id = (SELECT account_id FROM wallets WHERE id = ?)
+        WHERE tx_hash = ? AND account_id IS NULL"
+    )
+    .bind(wallet_id)
+    .bind(txid)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(result.rows_affected())
+}
+
+/// Imports a wallet export in one transaction, so a rejected or failed import leaves every
+/// wallet as it was. A per-output export replaces the UTXO set of each wallet it touches.
+#[tauri::command]
+pub async fn import_wallet_activity(
+    pool: State<'_, SqlitePool>,
+    file_path: String,
+    wallet_id: Option<String>,
+) -> Result<WalletActivityImportResult, String> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read file '{}': {}", file_path, e))?;
+
+    let (format, rows) = parse_wallet_export(&content)?;
+    println!("Parsed {} rows from {:?} wallet export", rows.len(), format);
+
+    let per_output = rows.first().is_some_and(|row| row.vout >= 0);
+    if rows.iter().any(|row| (row.vout >= 0) != per_output) {
+        return Err("Either every row of the export must have a vout or none of them".to_string());
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if let Some(wallet_id) = &wallet_id {
+        fetch_wallet(&mut *tx, wallet_id).await?;
+    }
+
+    // Rows are assigned to wallets before anything is stored, so each wallet is checked and
+    // its spent outputs removed once per file.
+    let mut matched_rows: Vec<(String, WalletActivityRow)> = Vec::new();
+
+    // Rows with addresses are matched against derived addresses. Every match can extend the
+    // derived range (gap limit), so unmatched rows are retried until nothing new matches.
+    let (mut pending, unaddressed): (Vec<WalletActivityRow>, Vec<WalletActivityRow>) =
+        rows.into_iter().partition(|row| row.address.is_some());
+
+    if !unaddressed.is_empty() {
+        let wallet_id = wallet_id.as_deref().ok_or_else(|| {
+            "This export does not contain addresses. Choose the wallet it belongs to".to_string()
+        })?;
+        matched_rows.extend(unaddressed.into_iter().map(|row| (wallet_id.to_string(), row)));
+    }
+
+    loop {
+        let mut unmatched = Vec::new();
+        let mut touched_wallets = HashSet::new();
+
+        for row in pending {
+            let address = row.address.as_deref().unwrap_or_default();
+            let matched = sqlx::query(
+                "SELECT wallet_id FROM wallet_addresses WHERE address = ? AND (? IS NULL OR wallet_id = ?)"
+            )
+            .bind(address)
+            .bind(&wallet_id)
+            .bind(&wallet_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+            let Some(matched) = matched else {
+                unmatched.push(row);
+                continue;
+            };
+            let matched_wallet_id: String = matched.get("wallet_id");
+
+            sqlx::query("UPDATE wallet_addresses SET used = 1 WHERE address = ?")
+                .bind(address)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+
+            touched_wallets.insert(matched_wallet_id.clone());
+            matched_rows.push((matched_wallet_id, row));
+        }
+
+        let mut derived_count = 0;
+        for touched_wallet_id in &touched_wallets {
+            let wallet = fetch_wallet(&mut *tx, touched_wallet_id).await?;
+            derived_count += extend_wallet_addresses(&mut tx, &wallet).await?;
+        }
+
+        pending = unmatched;
+        if pending.is_empty() || derived_count == 0 {
+            break;
+        }
+    }
+
+    let mut outputs_by_wallet: HashMap<&str, HashSet<(String, i64)>> = HashMap::new();
+    for (matched_wallet_id, row) in &matched_rows {
+        outputs_by_wallet
+            .entry(matched_wallet_id.as_str())
+            .or_default()
+            .insert((row.txid.clone(), row.vout));
+    }
+
+    let mut spent_count = 0;
+    for (matched_wallet_id, outputs) in &outputs_by_wallet {
+        check_activity_granularity(&mut tx, matched_wallet_id, per_output).await?;
+        if per_output {
+            spent_count += remove_spent_outputs(&mut tx, matched_wallet_id, outputs).await?;
+        }
+    }
+
+    let mut imported_count = 0;
+    let mut confirmed_count = 0;
+    let mut duplicate_count = 0;
+    let mut matched_onchain_fees = 0;
+    let mut matched_txids = HashSet::new();
+
+    for (matched_wallet_id, row) in &matched_rows {
+        match insert_wallet_activity(&mut tx, matched_wallet_id, row).await? {
+            ActivityInsert::Inserted => imported_count += 1,
+            ActivityInsert::Confirmed => confirmed_count += 1,
+            ActivityInsert::Duplicate => duplicate_count += 1,
+        }
+        if row.amount_sats < 0 && matched_txids.insert(row.txid.clone()) {
+            matched_onchain_fees += match_onchain_fees(&mut tx, matched_wallet_id, &row.txid).await?;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let result = WalletActivityImportResult {
+        format: format!("{:?}", format),
+        imported_count,
+        confirmed_count,
+        duplicate_count,
+        spent_count,
+        unmatched_count: pending.len(),
+        matched_onchain_fees,
+    };
+
+    println!("Wallet activity import completed: {:?}", result);
+    Ok(result)
+}
+
+// ============================================================================
+// BALANCES
+// ============================================================================
+
+pub(crate) async fn calculate_wallet_balances(pool: &SqlitePool) -> Result<Vec<WalletBalance>, String> {
+    let rows = sqlx::query(
+        "SELECT
+            w.id,
+            w.account_id,
+            w.label,
+            COALESCE(SUM(a.amount_sats), 0) as balance_sats,
+            COUNT(a.id) as activity_count,
+            MAX(a.timestamp) as last_activity
+        FROM wallets w
+        LEFT JOIN wallet_activity a ON a.wallet_id = w.id
+        GROUP BY w.id, w.account_id, w.label
+        ORDER BY w.label ASC"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| WalletBalance {
+            wallet_id: row.get("id"),
+            account_id: row.get("account_id"),
+            label: row.get("label"),
+            balance_sats: row.get("balance_sats"),
+            activity_count: row.get("activity_count"),
+            last_activity: row.get("last_activity"),
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_wallet_reconciliation(
+    pool: State<'_, SqlitePool>,
+) -> Result<ColdStorageReconciliation, String> {
+    let wallets = calculate_wallet_balances(pool.inner()).await?;
+    let overview = calculate_overview_metrics(pool.inner(), &OverviewFilter::default()).await?;
+
+    let total_wallet_sats: i64 = wallets.iter().map(|w| w.balance_sats).sum();
+
+    let reconciliation = ColdStorageReconciliation {
+        wallets,
+        total_wallet_sats,
+        current_sats: overview.current_sats,
+        difference_sats: overview.current_sats - total_wallet_sats,
+    };
+
+    println!(
+        "Cold storage reconciliation: {} sats in wallets, {} sats tracked, difference {}",
+        reconciliation.total_wallet_sats, reconciliation.current_sats, reconciliation.difference_sats
+    );
+    Ok(reconciliation)
+}
@@ -2,10 +2,10 @@ mod models;
 mod commands;
 mod database;
 
-use commands::exchange_transaction::{create_exchange_transaction, get_exchange_transactions, update_exchange_transaction, delete_exchange_transaction};
-use commands::onchain_fee::{create_onchain_fee, get_onchain_fees, update_onchain_fee, delete_onchain_fee};
-use commands::unified_events::get_unified_events;
-use commands::api::{fetch_bitcoin_price, fetch_announcements};
+use commands::exchange_transaction::{create_exchange_transaction, get_exchange_transactions, get_exchange_transactions_after, update_exchange_transaction, delete_exchange_transaction};
+use commands::onchain_fee::{create_onchain_fee, get_onchain_fees, get_onchain_fees_after, update_onchain_fee, delete_onchain_fee};
+use commands::unified_events::{get_unified_events, get_unified_events_after, search_events};
+use commands::api::{fetch_bitcoin_price, fetch_announcements, get_cached_bitcoin_price};
 use commands::activity_tool::get_activity_metrics;
 use commands::menu_tools::{
     import_sat_tracker_v1_data, 
@@ -19,7 +19,70 @@ use commands::menu_tools::{
     create_undocumented_lumpsum_transactions,
     quit_app
 };
-use commands::overview_tool::get_overview_metrics;
+use commands::overview_tool::{get_overview_breakdown, get_overview_metrics};
+use commands::account::{create_account, get_accounts, update_account, delete_account};
+use commands::watch_only_wallet::{
+    create_watch_only_wallet,
+    get_watch_only_wallets,
+    update_wallet_gap_limits,
+    delete_watch_only_wallet,
+    get_wallet_addresses,
+    import_wallet_activity,
+    get_wallet_reconciliation
+};
+use commands::reconciliation::{
+    create_reconciliation,
+    get_reconciliations,
+    delete_reconciliation,
+    create_reconciliation_adjustment,
+    get_balance_adjustments,
+    delete_balance_adjustment
+};
+use commands::lightning_payment::{
+    create_lightning_payment,
+    get_lightning_payments,
+    update_lightning_payment,
+    delete_lightning_payment,
+    import_lightning_payments
+};
+use commands::spending_event::{
+    create_spending_event,
+    get_spending_events,
+    update_spending_event,
+    delete_spending_event,
+    get_spending_summary
+};
+use commands::tag::{
+    create_tag,
+    get_tags,
+    update_tag,
+    merge_tags,
+    delete_tag,
+    assign_tags,
+    unassign_tags
+};
+use commands::time_buckets::{get_time_buckets};
+use commands::price_history::{import_price_history, get_price_history};
+use commands::fee_analysis::{get_fee_analysis};
+use commands::performance::{get_performance_metrics};
+use commands::strategy_simulation::{get_strategy_comparison};
+use commands::timing_score::{get_purchase_timing_scores};
+use commands::goal::{
+    create_goal,
+    get_goals,
+    update_goal,
+    delete_goal,
+    get_goal_progress
+};
+use commands::achievement::{get_achievements};
+use commands::dca_plan::{
+    create_dca_plan,
+    get_dca_plans,
+    update_dca_plan,
+    delete_dca_plan,
+    get_dca_plan_adherence
+};
+use commands::settings::{get_settings, update_settings, reset_settings};
 use tauri::{Emitter, menu::{Menu, MenuItem, Submenu, PredefinedMenuItem}, AppHandle, Manager};
 
 // Add these helper functions before the main run() function
@@ -111,13 +174,16 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             create_exchange_transaction,
             get_exchange_transactions,
+            get_exchange_transactions_after,
             update_exchange_transaction,
             delete_exchange_transaction,
             get_overview_metrics,
+            get_overview_breakdown,
             import_sat_tracker_v1_data,
             create_undocumented_lumpsum_transactions,
             fetch_bitcoin_price,
             fetch_announcements,
+            get_cached_bitcoin_price,
             check_database_status,
             validate_database_password,
             encrypt_database,
@@ -129,9 +195,67 @@ pub fn run() {
             analyze_csv_file,
             create_onchain_fee,
             get_onchain_fees,
+            get_onchain_fees_after,
             update_onchain_fee,
             delete_onchain_fee,
             get_unified_events,
+            get_unified_events_after,
+            search_events,
+            create_account,
+            get_accounts,
+            update_account,
+            delete_account,
+            create_watch_only_wallet,
+            get_watch_only_wallets,
+            update_wallet_gap_limits,
+            delete_watch_only_wallet,
+            get_wallet_addresses,
+            import_wallet_activity,
+            get_wallet_reconciliation,
+            create_reconciliation,
+            get_reconciliations,
+            delete_reconciliation,
+            create_reconciliation_adjustment,
+            get_balance_adjustments,
+            delete_balance_adjustment,
+            create_lightning_payment,
+            get_lightning_payments,
+            update_lightning_payment,
+            delete_lightning_payment,
+            import_lightning_payments,
+            create_spending_event,
+            get_spending_events,
+            update_spending_event,
+            delete_spending_event,
+            get_spending_summary,
+            create_tag,
+            get_tags,
+            update_tag,
+            merge_tags,
+            delete_tag,
+            assign_tags,
+            unassign_tags,
+            get_time_buckets,
+            import_price_history,
+            get_price_history,
+            get_fee_analysis,
+            get_performance_metrics,
+            get_strategy_comparison,
+            get_purchase_timing_scores,
+            create_goal,
+            get_goals,
+            update_goal,
+            delete_goal,
+            get_goal_progress,
+            get_achievements,
+            create_dca_plan,
+            get_dca_plans,
+            update_dca_plan,
+            delete_dca_plan,
+            get_dca_plan_adherence,
+            get_settings,
+            update_settings,
+            reset_settings,
             quit_app
         ])
         .run(tauri::generate_context!())
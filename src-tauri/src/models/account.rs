@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub id: String,
+    pub name: String,
+    pub kind: AccountKind,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AccountKind {
+    Exchange,
+    Wallet,
+    Lightning,
+}
+
+impl std::fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountKind::Exchange => write!(f, "exchange"),
+            AccountKind::Wallet => write!(f, "wallet"),
+            AccountKind::Lightning => write!(f, "lightning"),
+        }
+    }
+}
+
+impl std::str::FromStr for AccountKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exchange" => Ok(AccountKind::Exchange),
+            "wallet" => Ok(AccountKind::Wallet),
+            "lightning" => Ok(AccountKind::Lightning),
+            _ => Err(format!("Invalid account kind: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAccountRequest {
+    pub name: String,
+    pub kind: AccountKind,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateAccountRequest {
+    pub name: String,
+    pub kind: AccountKind,
+}
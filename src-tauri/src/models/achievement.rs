@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EarnedAchievement {
+    pub key: String,
+    pub title: String,
+    pub category: String, // "holdings", "streak" or "hodl"
+    pub reached_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpcomingAchievement {
+    pub key: String,
+    pub title: String,
+    pub category: String,
+    pub progress_percent: f64,
+    pub remaining: i64,             // in the unit below
+    pub remaining_unit: String,     // "sats", "weeks" or "days"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AchievementsReport {
+    pub earned: Vec<EarnedAchievement>, // oldest first
+    pub upcoming: Vec<UpcomingAchievement>,
+}
@@ -2,23 +2,55 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActivityMetrics {
-    pub current_streak_weeks: i32,
-    pub longest_streak_weeks: i32,
+    pub current_streak_weeks: i32, // in periods of primary_streak_cadence, weeks by default
+    pub longest_streak_weeks: i32, // in periods of primary_streak_cadence
     pub sats_stacked_this_year: i64,
     pub consistency_score_percent: f64,
     pub best_stacking_day: Option<String>,
     pub best_day_percentage: f64,
     pub consistency_rating: String,
-    pub weeks_to_next_milestone: Option<i32>,
+    pub weeks_to_next_milestone: Option<i32>, // in periods of primary_streak_cadence
     pub next_milestone_description: Option<String>,
     pub heatmap_data: Vec<YearHeatmapData>,
+    pub streaks: Vec<CadenceStreak>, // one entry per cadence, daily through monthly
+    pub primary_streak_cadence: StreakCadence, // the cadence chosen in settings
+    pub timezone: String,
+    pub week_start: String, // weekday weeks start on, e.g. "Mon"
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreakCadence {
+    Daily,
+    Weekly,
+    Biweekly, // two-week periods, aligned to the week of the first buy
+    Monthly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CadenceStreak {
+    pub cadence: StreakCadence,
+    pub current_streak: i32, // consecutive periods with a buy, still active if the last buy was this or the previous period
+    pub longest_streak: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapMetric {
+    #[default]
+    Sats, // sats bought
+    Fiat, // fiat invested in cents
+    Buys, // number of buys
+    NetFlow, // net sats in or out, including sells, spending and fees
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct YearHeatmapData {
     pub year: i32,
-    pub weeks: Vec<WeekData>,
+    pub weeks: Vec<WeekData>, // every week touching the year, 53 or 54 when the year needs it
     pub max_sats: i64,
+    pub metric: HeatmapMetric,
+    pub max_value: i64, // largest absolute metric value of any day
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,5 +62,6 @@ pub struct WeekData {
 pub struct DayData {
     pub date: String, // ISO date string (YYYY-MM-DD)
     pub sats: i64,
-    pub level: i32, // 0-4 for color intensity
+    pub level: i32, // 0-4 for color intensity, from the absolute metric value
+    pub value: i64, // metric value, negative for net outflow days
 }
@@ -0,0 +1,116 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanFrequency {
+    Daily,
+    Weekly,
+    Biweekly,
+    Monthly,
+}
+
+impl std::fmt::Display for PlanFrequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanFrequency::Daily => write!(f, "daily"),
+            PlanFrequency::Weekly => write!(f, "weekly"),
+            PlanFrequency::Biweekly => write!(f, "biweekly"),
+            PlanFrequency::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+impl std::str::FromStr for PlanFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(PlanFrequency::Daily),
+            "weekly" => Ok(PlanFrequency::Weekly),
+            "biweekly" => Ok(PlanFrequency::Biweekly),
+            "monthly" => Ok(PlanFrequency::Monthly),
+            _ => Err(format!("Invalid plan frequency: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DcaPlan {
+    pub id: String,
+    pub name: String,
+    pub amount_cents: i64,
+    pub frequency: PlanFrequency,
+    pub day_of_week: Option<u32>,  // 0 = Monday, defaults to the start date's weekday
+    pub day_of_month: Option<u32>, // defaults to the start date's day
+    pub provider: Option<String>,
+    pub account_id: Option<String>,
+    pub timezone: Option<String>,
+    pub grace_days: i64,
+    pub start_date: DateTime<Utc>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateDcaPlanRequest {
+    pub name: String,
+    pub amount_cents: i64,
+    pub frequency: PlanFrequency,
+    pub day_of_week: Option<u32>,
+    pub day_of_month: Option<u32>,
+    pub provider: Option<String>,
+    pub account_id: Option<String>,
+    pub timezone: Option<String>,
+    pub grace_days: Option<i64>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDcaPlanRequest {
+    pub name: String,
+    pub amount_cents: i64,
+    pub frequency: PlanFrequency,
+    pub day_of_week: Option<u32>,
+    pub day_of_month: Option<u32>,
+    pub provider: Option<String>,
+    pub account_id: Option<String>,
+    pub timezone: Option<String>,
+    pub grace_days: Option<i64>,
+    pub start_date: DateTime<Utc>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanOccurrence {
+    pub scheduled_date: NaiveDate, // local to the plan's timezone
+    pub status: String,            // "on_time", "late", "missed" or "pending" while still within grace
+    pub days_late: Option<i64>,
+    pub buy_ids: Vec<String>,
+    pub actual_cents: i64, // subtotal plus fees of the matched buys
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanExtraBuy {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub amount_sats: i64,
+    pub actual_cents: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DcaPlanAdherence {
+    pub plan: DcaPlan,
+    pub expected_count: i64, // occurrences due so far, pending ones excluded
+    pub on_time_count: i64,
+    pub late_count: i64,
+    pub missed_count: i64,
+    pub extra_count: i64,
+    pub adherence_percent: Option<f64>, // on time or late, over expected
+    pub on_time_percent: Option<f64>,
+    pub expected_cents: i64,
+    pub actual_cents: i64, // matched and extra buys
+    pub occurrences: Vec<PlanOccurrence>,
+    pub extra_buys: Vec<PlanExtraBuy>,
+}
@@ -14,6 +14,7 @@ pub struct ExchangeTransaction {
     pub timestamp: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub provider_id: Option<String>,
+    pub account_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +61,7 @@ pub struct CreateExchangeTransactionRequest {
     pub memo: Option<String>,
     pub timestamp: DateTime<Utc>,
     pub provider_id: Option<String>,
+    pub account_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -71,6 +73,8 @@ pub struct UpdateExchangeTransactionRequest {
     pub memo: Option<String>,
     pub timestamp: DateTime<Utc>,
     pub provider_id: Option<String>,
+    pub account_id: Option<String>, // None keeps the linked account
+    pub clear_account: Option<bool>, // true unlinks the account
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,3 +86,12 @@ pub struct PaginatedBitcoinTransactions {
     pub total_pages: u32,
     pub has_more: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysetBitcoinTransactions {
+    pub transactions: Vec<ExchangeTransaction>,
+    pub page_size: u32,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    pub total_count: Option<i64>, // only computed when requested
+}
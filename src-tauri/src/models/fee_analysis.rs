@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuyFeeDetail {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub provider: String,
+    pub amount_sats: i64,
+    pub subtotal_cents: i64,
+    pub fee_cents: i64,
+    pub fee_rate_percent: f64,           // fee_cents / subtotal_cents
+    pub paid_price_cents: i64,           // per BTC, fees excluded
+    pub market_price_cents: Option<i64>, // stored close of the buy's UTC day, None without one
+    pub day_low_cents: Option<i64>,
+    pub day_high_cents: Option<i64>,
+    pub spread_percent: Option<f64>,     // paid price above the day's close
+    pub min_spread_percent: Option<f64>, // paid price above the day's high, 0 inside the range
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeeTrend {
+    pub group: String, // "2024-03" for monthly trends, the provider name for provider trends
+    pub buy_count: i64,
+    pub volume_cents: i64,
+    pub fees_cents: i64,
+    pub fee_rate_percent: Option<f64>,
+    pub avg_spread_percent: Option<f64>, // volume weighted, over buys with a market price only
+    pub total_cost_percent: Option<f64>, // fee rate plus spread
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeeAnalysis {
+    pub buys: Vec<BuyFeeDetail>,
+    pub monthly: Vec<FeeTrend>,
+    pub by_provider: Vec<FeeTrend>,
+    pub total_onchain_fees_sats: i64,
+    pub onchain_fees_percent_of_stack: Option<f64>, // fees paid in the period over the stack at its end
+    pub onchain_fees_fiat_cents: i64, // valued at the market price on the day each fee was paid
+    pub unpriced_onchain_fee_count: i64,
+}
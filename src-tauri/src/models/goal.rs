@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: String,
+    pub name: String,
+    pub target_sats: i64,
+    pub start_date: Option<DateTime<Utc>>, // None counts total holdings instead of sats stacked since
+    pub target_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateGoalRequest {
+    pub name: String,
+    pub target_sats: i64,
+    pub start_date: Option<DateTime<Utc>>,
+    pub target_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateGoalRequest {
+    pub name: String,
+    pub target_sats: i64,
+    pub start_date: Option<DateTime<Utc>>,
+    pub target_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoalProgress {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub current_sats: i64,
+    pub remaining_sats: i64,
+    pub progress_percent: f64,
+    pub is_complete: bool,
+    pub weeks_remaining: Option<f64>,       // until target_date, None without one
+    pub required_weekly_sats: Option<i64>,  // to reach the target by target_date
+    pub trailing_weekly_sats: i64,          // net sats per week over the trailing window
+    pub projected_completion_date: Option<DateTime<Utc>>, // None when the trailing rate is not positive
+    pub on_track: Option<bool>,             // projected to finish by target_date
+}
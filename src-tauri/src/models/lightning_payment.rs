@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningPayment {
+    pub id: String,
+    pub direction: PaymentDirection,
+    pub amount_msats: i64,
+    pub routing_fee_msats: i64,
+    pub payment_hash: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub provider_id: Option<String>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PaymentDirection {
+    Outgoing,
+    Incoming,
+}
+
+impl std::fmt::Display for PaymentDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaymentDirection::Outgoing => write!(f, "outgoing"),
+            PaymentDirection::Incoming => write!(f, "incoming"),
+        }
+    }
+}
+
+impl std::str::FromStr for PaymentDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "outgoing" => Ok(PaymentDirection::Outgoing),
+            "incoming" => Ok(PaymentDirection::Incoming),
+            _ => Err(format!("Invalid payment direction: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateLightningPaymentRequest {
+    pub direction: PaymentDirection,
+    pub amount_msats: i64,
+    pub routing_fee_msats: i64,
+    pub payment_hash: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub provider_id: Option<String>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLightningPaymentRequest {
+    pub direction: PaymentDirection,
+    pub amount_msats: i64,
+    pub routing_fee_msats: i64,
+    pub payment_hash: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedLightningPayments {
+    pub payments: Vec<LightningPayment>,
+    pub total_count: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LightningImportResult {
+    pub format: String,
+    pub imported_count: usize,
+    pub duplicate_count: usize,
+    pub skipped_count: usize, // failed or pending payments
+}
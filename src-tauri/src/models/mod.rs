@@ -3,3 +3,19 @@ pub mod overview;
 pub mod activity_metrics;
 pub mod onchain_fee;
 pub mod unified_events;
+pub mod account;
+pub mod wallet;
+pub mod reconciliation;
+pub mod lightning_payment;
+pub mod spending_event;
+pub mod tag;
+pub mod time_buckets;
+pub mod price_history;
+pub mod fee_analysis;
+pub mod performance;
+pub mod strategy_simulation;
+pub mod timing_score;
+pub mod goal;
+pub mod achievement;
+pub mod dca_plan;
+pub mod settings;
@@ -10,6 +10,7 @@ pub struct OnchainFee {
     pub timestamp: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub tx_hash: Option<String>,
+    pub account_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +19,7 @@ pub struct CreateOnchainFeeRequest {
     pub memo: Option<String>,
     pub timestamp: DateTime<Utc>,
     pub tx_hash: Option<String>,
+    pub account_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,6 +28,8 @@ pub struct UpdateOnchainFeeRequest {
     pub memo: Option<String>,
     pub timestamp: DateTime<Utc>,
     pub tx_hash: Option<String>,
+    pub account_id: Option<String>, // None keeps the linked account
+    pub clear_account: Option<bool>, // true unlinks the account
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -37,3 +41,12 @@ pub struct PaginatedOnchainFees {
     pub total_pages: u32,
     pub has_more: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysetOnchainFees {
+    pub fees: Vec<OnchainFee>,
+    pub page_size: u32,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    pub total_count: Option<i64>, // only computed when requested
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,9 +10,43 @@ pub struct OverviewMetrics {
     pub avg_sell_price: Option<f64>,
     pub fiat_extracted_cents: i64,
     pub total_sats_spent: i64,
+    pub total_sats_spent_on_goods: i64,
+    pub total_spending_fiat_cents: i64,
+    pub realized_gain_cents: i64,
+    pub unpriced_disposal_count: i64, // spending left out of realized_gain_cents for lack of a price
     pub total_onchain_fees_paid_sats: i64,
+    pub net_adjustment_sats: i64,
+    pub total_lightning_sent_msats: i64,
+    pub total_lightning_received_msats: i64,
+    pub total_lightning_routing_fees_msats: i64,
     pub sats_stacked_7d: i64,
     pub usd_invested_7d_cents: i64,
     pub sats_stacked_31d: i64,
     pub usd_invested_31d_cents: i64,
+    pub start_date: Option<DateTime<Utc>>,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Period and tags to compute `OverviewMetrics` for. Leaving everything unset covers all events
+/// up to now.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverviewFilter {
+    pub start_date: Option<DateTime<Utc>>,
+    pub as_of: Option<DateTime<Utc>>,
+    pub tag_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverviewBreakdown {
+    pub group_key: Option<String>, // provider name or account id, None for events without an account
+    pub group_label: String,
+    pub transaction_count: i64,
+    pub sats_acquired: i64,
+    pub sats_sold: i64,
+    pub fiat_invested_cents: i64,
+    pub fiat_extracted_cents: i64,
+    pub avg_buy_price: Option<f64>,
+    pub fiat_fees_cents: i64,
+    pub effective_fee_percent: Option<f64>, // fiat fees as a share of buy and sell volume
 }
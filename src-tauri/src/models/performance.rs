@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub start_date: Option<DateTime<Utc>>, // None means since the first event
+    pub end_date: DateTime<Utc>,
+    pub starting_sats: i64,
+    pub starting_value_cents: i64, // holdings carried into the period, valued at the start price
+    pub ending_sats: i64,
+    pub terminal_value_cents: i64,
+    pub invested_cents: i64, // buys including fees, plus unpriced inflows (lightning, adjustments) at the day's price
+    pub extracted_cents: i64, // sells net of fees, spending, and unpriced outflows at the day's price
+    pub net_profit_cents: i64,
+    pub simple_roi_percent: Option<f64>,
+    pub xirr_percent: Option<f64>, // annualized money-weighted return
+    pub twr_percent: Option<f64>,  // time-weighted return over the period
+    pub twr_annualized_percent: Option<f64>,
+    pub unpriced_flow_days: i64, // days with cash flows but no stored price, TWR is None when > 0
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyPrice {
+    pub date: NaiveDate,
+    pub open_cents: Option<i64>,
+    pub high_cents: Option<i64>,
+    pub low_cents: Option<i64>,
+    pub close_cents: i64,
+    pub source: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceHistoryImportResult {
+    pub imported_count: usize,
+    pub skipped_count: usize,
+    pub first_date: Option<NaiveDate>,
+    pub last_date: Option<NaiveDate>,
+}
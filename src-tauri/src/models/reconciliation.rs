@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceAdjustment {
+    pub id: String,
+    pub account_id: Option<String>,
+    pub amount_sats: i64, // signed: positive adds sats, negative removes them
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reconciliation {
+    pub id: String,
+    pub account_id: Option<String>, // None = the whole stack
+    pub observed_sats: i64,
+    pub observed_at: DateTime<Utc>,
+    pub implied_sats: i64, // balance implied by recorded events up to observed_at
+    pub discrepancy_sats: i64, // observed_sats - implied_sats
+    pub memo: Option<String>,
+    pub adjustment_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateReconciliationRequest {
+    pub account_id: Option<String>,
+    pub observed_sats: i64,
+    pub observed_at: DateTime<Utc>,
+    pub memo: Option<String>,
+}
@@ -0,0 +1,62 @@
+use crate::models::activity_metrics::StreakCadence;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostBasisMethod {
+    #[default]
+    Average,
+    Fifo,
+    Lifo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceProviderKind {
+    #[default]
+    Dprogram, // the hosted proxy at `price_api_host`
+    CustomEndpoint, // a self-hosted BTCPay, mempool or similar JSON endpoint
+    LocalFile, // a static JSON file, no network access at all
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub base_currency: String, // ISO 4217 code, e.g. "USD"
+    pub timezone: Option<String>, // IANA name, None for UTC
+    pub week_start: String, // weekday name, e.g. "Mon" or "Sunday"
+    pub cost_basis_method: CostBasisMethod,
+    pub price_provider: PriceProviderKind,
+    pub price_fallback_providers: Vec<PriceProviderKind>, // tried in order when the main provider fails
+    pub price_api_host: Option<String>, // None uses the built-in host
+    pub price_endpoint_url: Option<String>, // for custom endpoints, "{currency}" is replaced by the base currency
+    pub price_file_path: Option<String>,
+    pub offline_mode: bool, // refuse every network call and serve cached data instead
+    pub proxy_url: Option<String>, // SOCKS5 proxy for every request, e.g. "socks5h://127.0.0.1:9050" for Tor
+    pub coinbase_grouping_window_seconds: i64, // Coinbase fills this close together import as one transaction
+    pub streak_cadence: StreakCadence, // the cadence the activity view highlights
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            base_currency: "USD".to_string(),
+            timezone: None,
+            week_start: "Mon".to_string(),
+            cost_basis_method: CostBasisMethod::Average,
+            price_provider: PriceProviderKind::Dprogram,
+            price_fallback_providers: Vec::new(),
+            price_api_host: None,
+            price_endpoint_url: None,
+            price_file_path: None,
+            offline_mode: false,
+            proxy_url: None,
+            coinbase_grouping_window_seconds: 5,
+            streak_cadence: StreakCadence::Weekly,
+        }
+    }
+}
+
+/// Settings to change, keyed like `AppSettings`. Keys that are left out keep their stored value
+/// and `null` clears an optional setting.
+pub type SettingsPatch = serde_json::Map<String, serde_json::Value>;
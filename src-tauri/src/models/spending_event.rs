@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingEvent {
+    pub id: String,
+    pub amount_sats: i64,
+    pub fiat_value_cents: Option<i64>,
+    pub merchant: Option<String>,
+    pub category: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSpendingEventRequest {
+    pub amount_sats: i64,
+    pub fiat_value_cents: Option<i64>,
+    pub merchant: Option<String>,
+    pub category: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSpendingEventRequest {
+    pub amount_sats: i64,
+    pub fiat_value_cents: Option<i64>,
+    pub merchant: Option<String>,
+    pub category: Option<String>,
+    pub memo: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedSpendingEvents {
+    pub spending_events: Vec<SpendingEvent>,
+    pub total_count: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpendingSummary {
+    pub group: String, // category or merchant name, "Uncategorized" when missing
+    pub event_count: i64,
+    pub total_sats: i64,
+    pub total_fiat_cents: i64,
+}
@@ -0,0 +1,24 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyResult {
+    pub strategy: String, // "actual", "lump_sum", "weekly_dca", "monthly_dca" or "buy_the_dip"
+    pub fiat_invested_cents: i64,
+    pub fiat_uninvested_cents: i64, // contributions a strategy never deployed
+    pub sats_acquired: i64,
+    pub purchase_count: i64,
+    pub unpriced_days: i64, // scheduled buys without a stored price, moved to the next priced day
+    pub avg_price_cents: Option<i64>, // per BTC, fees included
+    pub sats_vs_actual_percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyComparison {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub total_contributions_cents: i64,
+    pub fee_rate_percent: f64, // the actual average fee rate, applied to every simulated buy
+    pub dip_moving_average_days: i64,
+    pub strategies: Vec<StrategyResult>,
+}
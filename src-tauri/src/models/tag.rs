@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagWithUsage {
+    #[serde(flatten)]
+    pub tag: Tag,
+    pub event_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTagRequest {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTagRequest {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// Identifies a single event across the event tables, using the unified events `record_type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRef {
+    pub record_type: String,
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkTagRequest {
+    pub tag_ids: Vec<String>,
+    pub events: Vec<EventRef>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkTagResult {
+    pub changed_count: i64,
+    pub unchanged_count: i64,
+}
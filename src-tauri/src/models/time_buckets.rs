@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketSize {
+    Daily,
+    Weekly, // starting on the week start from settings
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBucket {
+    pub label: String, // "2024-03-15", "2024-W11" (ISO week holding most of its days), "2024-03" or "2024"
+    pub bucket_start: DateTime<Utc>, // local midnight the bucket starts at, in UTC
+    pub sats_bought: i64,
+    pub priced_sats_bought: i64, // sats from buys with a recorded subtotal
+    pub sats_sold: i64,
+    pub fiat_invested_cents: i64,
+    pub fiat_extracted_cents: i64,
+    pub fiat_fees_cents: i64,
+    pub onchain_fees_sats: i64,
+    pub sats_spent: i64,
+    pub fiat_spent_cents: i64, // spending with a recorded fiat value
+    pub lightning_received_sats: i64,
+    pub lightning_sent_sats: i64, // including routing fees
+    pub adjustment_sats: i64, // signed net of balance adjustments
+    pub event_count: i64,
+    pub avg_buy_price: Option<f64>, // over priced_sats_bought only
+}
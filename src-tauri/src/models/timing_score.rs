@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuyTimingScore {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub provider: String,
+    pub weekday: String, // in the requested timezone
+    pub hour: u32,       // in the requested timezone
+    pub paid_price_cents: i64, // subtotal over amount, fees excluded
+    pub low_cents: Option<i64>,
+    pub high_cents: Option<i64>,
+    pub close_cents: Option<i64>,
+    pub timing_score: Option<f64>, // 100 bought at the day's low, 0 at the high; None without a range
+    pub vs_close_percent: Option<f64>, // paid price below (negative) or above the day's close
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimingScoreGroup {
+    pub group: String, // weekday name, hour "00".."23" or provider
+    pub buy_count: i64,
+    pub scored_count: i64,
+    pub avg_timing_score: Option<f64>,
+    pub avg_vs_close_percent: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimingScoreReport {
+    pub timezone: String,
+    pub buys: Vec<BuyTimingScore>,
+    pub overall: TimingScoreGroup,
+    pub by_weekday: Vec<TimingScoreGroup>, // Monday first
+    pub by_hour: Vec<TimingScoreGroup>,
+    pub by_provider: Vec<TimingScoreGroup>,
+}
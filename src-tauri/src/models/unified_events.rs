@@ -1,23 +1,37 @@
+use crate::models::tag::Tag;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnifiedEvent {
     pub id: String,
-    pub record_type: String, // "exchange_transaction" or "onchain_fee"
+    pub record_type: String, // "exchange_transaction", "onchain_fee", "balance_adjustment", "lightning_payment" or "spending"
     pub amount_sats: i64,
     pub memo: Option<String>,
     pub timestamp: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub account_id: Option<String>,
+    pub provider: String, // "coinbase", "river", "sat_tracker_v1", "lnd", "cln", "phoenix" or "manual"
     
-    // Exchange-specific fields (None for onchain fees)
+    // Exchange-specific fields (None for other record types, subtotal is the fiat value for spending)
     pub subtotal_cents: Option<i64>,
     pub fee_cents: Option<i64>,
     pub provider_id: Option<String>,
-    pub transaction_type: Option<String>, // "buy", "sell", or "fee"
+    pub transaction_type: Option<String>, // "buy", "sell", "fee", "adjustment", "lightning_outgoing", "lightning_incoming" or "spend"
     
-    // Onchain-specific fields (None for exchange transactions)
+    // Onchain-specific fields (None for other record types)
     pub tx_hash: Option<String>,
+
+    // Lightning-specific fields (None for other record types)
+    pub amount_msats: Option<i64>,
+    pub routing_fee_msats: Option<i64>,
+    pub payment_hash: Option<String>,
+
+    // Spending-specific fields (None for other record types)
+    pub merchant: Option<String>,
+    pub category: Option<String>,
+
+    pub tags: Vec<Tag>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,3 +43,39 @@ pub struct PaginatedUnifiedEvents {
     pub total_pages: u32,
     pub has_more: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysetUnifiedEvents {
+    pub events: Vec<UnifiedEvent>,
+    pub page_size: u32,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    pub total_count: Option<i64>, // only computed when requested
+}
+
+/// Optional server-side filters and sort order for `get_unified_events`. Every filter left as
+/// `None` is ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnifiedEventsFilter {
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub transaction_types: Option<Vec<String>>, // "buy", "sell", "fee", ...
+    pub provider: Option<String>,
+    pub account_id: Option<String>,
+    pub tag_ids: Option<Vec<String>>,
+    pub min_amount_sats: Option<i64>,
+    pub max_amount_sats: Option<i64>,
+    pub min_fiat_cents: Option<i64>,
+    pub max_fiat_cents: Option<i64>,
+    pub memo_contains: Option<String>,
+    pub sort_by: Option<String>,        // any unified event column, defaults to "timestamp"
+    pub sort_direction: Option<String>, // "asc" or "desc", defaults to "desc"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventSearchResult {
+    pub event: UnifiedEvent,
+    pub snippet: String, // best matching column, matched terms wrapped in <mark></mark>
+    pub rank: f64,       // bm25 score, lower is a better match
+}
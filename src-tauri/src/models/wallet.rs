@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchOnlyWallet {
+    pub id: String,
+    pub account_id: String,
+    pub label: String,
+    pub descriptor: String,
+    pub script_type: String, // "p2pkh", "p2sh-p2wpkh", "p2wpkh" or "p2tr"
+    pub network: String,     // "bitcoin" or "testnet"
+    pub receive_gap_limit: u32,
+    pub change_gap_limit: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWatchOnlyWalletRequest {
+    pub account_id: Option<String>, // a new wallet account is created when omitted
+    pub label: String,
+    pub descriptor: String, // xpub/ypub/zpub or a single-key output descriptor
+    pub script_type: Option<String>,
+    pub receive_gap_limit: Option<u32>,
+    pub change_gap_limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletAddress {
+    pub wallet_id: String,
+    pub chain: u32, // 0 = receive, 1 = change
+    pub derivation_index: u32,
+    pub address: String,
+    pub used: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletActivityImportResult {
+    pub format: String,
+    pub imported_count: usize,
+    pub confirmed_count: usize, // previously unconfirmed rows that now have a date
+    pub duplicate_count: usize,
+    pub spent_count: usize, // outputs removed because a UTXO export no longer lists them
+    pub unmatched_count: usize,
+    pub matched_onchain_fees: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletBalance {
+    pub wallet_id: String,
+    pub account_id: String,
+    pub label: String,
+    pub balance_sats: i64,
+    pub activity_count: i64,
+    pub last_activity: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColdStorageReconciliation {
+    pub wallets: Vec<WalletBalance>,
+    pub total_wallet_sats: i64,
+    pub current_sats: i64,
+    pub difference_sats: i64, // current_sats not accounted for by watch-only wallets
+}